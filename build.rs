@@ -0,0 +1,27 @@
+fn main() {
+    #[cfg(feature = "ffi")]
+    generate_header();
+}
+
+/// Generate the C header of `src/ffi.rs` into `OUT_DIR`.
+///
+/// `include/dex_helper.h` is the committed copy users build against, the
+/// `ffi` tests fail when it's out of date. Refresh it with
+/// `cp "$(find target -path '*/out/dex_helper.h' | head -n1)" include/`
+/// or `cbindgen --config cbindgen.toml --output include/dex_helper.h`.
+#[cfg(feature = "ffi")]
+fn generate_header() {
+    // cbindgen reads the whole crate, constants outside src/ffi.rs included
+    println!("cargo:rerun-if-changed=src");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+    let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let out_dir = std::env::var("OUT_DIR").unwrap();
+    let config = cbindgen::Config::from_file(format!("{crate_dir}/cbindgen.toml"))
+        .expect("cbindgen.toml is valid");
+    cbindgen::Builder::new()
+        .with_crate(&crate_dir)
+        .with_config(config)
+        .generate()
+        .expect("failed to generate the C header")
+        .write_to_file(format!("{out_dir}/dex_helper.h"));
+}
@@ -0,0 +1,24 @@
+//! Serve a chain snapshot as a local CKB node, then use its url as the app's RPC Endpoint.
+//!
+//! `cargo run --example mock_node --features mock-node -- examples/snapshot.json 127.0.0.1:8114`
+
+use dex_helper::mock_node::{ChainSnapshot, MockNode};
+
+fn main() -> std::io::Result<()> {
+    env_logger::init();
+
+    let mut args = std::env::args().skip(1);
+    let path = args
+        .next()
+        .unwrap_or_else(|| "examples/snapshot.json".to_owned());
+    let addr = args.next().unwrap_or_else(|| "127.0.0.1:8114".to_owned());
+
+    let snapshot: ChainSnapshot =
+        serde_json::from_str(&std::fs::read_to_string(&path)?).map_err(std::io::Error::other)?;
+    let node = MockNode::bind(&addr, snapshot)?;
+    println!("Mock CKB node serving {path} on {}", node.url());
+
+    loop {
+        std::thread::park();
+    }
+}
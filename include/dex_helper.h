@@ -0,0 +1,149 @@
+/* Generated by cbindgen from src/ffi.rs, don't edit. */
+
+#ifndef DEX_HELPER_H
+#define DEX_HELPER_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * `mode(2) || owner_script_hash(32) || price_base(4) || price_pow(4)`
+ */
+#define DEX_HELPER_ARGS_LEN 42
+
+#define DEX_HELPER_MAX_MODE 2
+
+/**
+ * `0x` + 2 hex digits per byte + the terminating NUL.
+ */
+#define DEX_HELPER_ARGS_HEX_LEN ((2 + (DEX_HELPER_ARGS_LEN * 2)) + 1)
+
+/**
+ * `0` on success, `1..=5` are the `DexHelperError` codes (the CLI exit codes).
+ */
+typedef enum DexHelperStatus {
+  DEX_HELPER_STATUS_OK = 0,
+  DEX_HELPER_STATUS_ARGS_DECODE_ERROR = 1,
+  DEX_HELPER_STATUS_LOCK_SCRIPT_HASH_ERROR = 2,
+  DEX_HELPER_STATUS_MODE_TOO_BIG = 3,
+  DEX_HELPER_STATUS_ARGS_LEN_ERROR = 4,
+  DEX_HELPER_STATUS_PRICE_OVERFLOW = 5,
+  /**
+   * A NULL pointer, a string that isn't UTF-8 or an unknown hash type.
+   */
+  DEX_HELPER_STATUS_INVALID_ARGUMENT = 6,
+} DexHelperStatus;
+
+/**
+ * Decoded dex lock args.
+ */
+typedef struct DexHelperArgs {
+  uint16_t mode;
+  uint8_t owner_script_hash[32];
+  uint32_t price_base;
+  uint32_t price_pow;
+} DexHelperArgs;
+
+/**
+ * A `u128` as two little endian halves, C has no portable 128 bit integer.
+ */
+typedef struct DexHelperU128 {
+  uint64_t lo;
+  uint64_t hi;
+} DexHelperU128;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Static description of a status, never NULL.
+ *
+ * Takes the status as an integer, any value C may pass is safe and values
+ * that are no `DexHelperStatus` get "Unknown status".
+ */
+const char *dex_helper_status_message(uint32_t status);
+
+/**
+ * Encode `args` into the 42 raw bytes of the lock args.
+ *
+ * # Safety
+ *
+ * `args` must point to a `DexHelperArgs` and `out` to `DEX_HELPER_ARGS_LEN` writable bytes.
+ */
+enum DexHelperStatus dex_helper_encode_args(const struct DexHelperArgs *args, uint8_t *out);
+
+/**
+ * Encode `args` as a NUL terminated `0x` prefixed hex string.
+ *
+ * # Safety
+ *
+ * `args` must point to a `DexHelperArgs` and `out` to
+ * `DEX_HELPER_ARGS_HEX_LEN` writable bytes.
+ */
+enum DexHelperStatus dex_helper_encode_args_hex(const struct DexHelperArgs *args, char *out);
+
+/**
+ * Decode `len` raw bytes of lock args.
+ *
+ * # Safety
+ *
+ * `bytes` must point to `len` readable bytes and `out` to a writable `DexHelperArgs`.
+ */
+enum DexHelperStatus dex_helper_decode_args(const uint8_t *bytes,
+                                            size_t len,
+                                            struct DexHelperArgs *out);
+
+/**
+ * Decode lock args from a NUL terminated hex string, the `0x` prefix is optional.
+ *
+ * # Safety
+ *
+ * `hex` must be a NUL terminated string and `out` point to a writable `DexHelperArgs`.
+ */
+enum DexHelperStatus dex_helper_decode_args_hex(const char *hex, struct DexHelperArgs *out);
+
+/**
+ * Exact total payment in shannons, `amount` only matters in UDT mode (0).
+ *
+ * # Safety
+ *
+ * `args` must point to a `DexHelperArgs` and `out` to a writable `DexHelperU128`.
+ */
+enum DexHelperStatus dex_helper_total_price(const struct DexHelperArgs *args,
+                                            struct DexHelperU128 amount,
+                                            struct DexHelperU128 *out);
+
+/**
+ * Script hash of a script, e.g. the owner lock hash.
+ *
+ * `hash_type` is the molecule byte: 0 data, 1 type, 2 data1, 4 data2.
+ *
+ * # Safety
+ *
+ * `code_hash` must point to 32 readable bytes, `args` to `args_len` readable
+ * bytes and `out` to 32 writable bytes.
+ */
+enum DexHelperStatus dex_helper_script_hash(const uint8_t *code_hash,
+                                            uint8_t hash_type,
+                                            const uint8_t *args,
+                                            size_t args_len,
+                                            uint8_t *out);
+
+/**
+ * Script hash of the dex lock with these args.
+ *
+ * # Safety
+ *
+ * `args` must point to a `DexHelperArgs` and `out` to 32 writable bytes.
+ */
+enum DexHelperStatus dex_helper_dex_lock_hash(const struct DexHelperArgs *args, uint8_t *out);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif // __cplusplus
+
+#endif /* DEX_HELPER_H */
@@ -0,0 +1,124 @@
+use bech32::{FromBase32, ToBase32, Variant};
+
+use crate::dex::strip_0x;
+use crate::rpc::Script;
+use crate::script::{hash_type_from_byte, hash_type_to_byte, ScriptError};
+
+/// Address payload tags (RFC 0021), only full format is still generated.
+const FULL_FORMAT: u8 = 0x00;
+const SHORT_FORMAT: u8 = 0x01;
+const FULL_DATA_FORMAT: u8 = 0x02;
+const FULL_TYPE_FORMAT: u8 = 0x04;
+
+const SECP256K1_BLAKE160_CODE_HASH: &str =
+    "0x9bd7e06f3ecf4be0f2fcd2188b23f1b9fcc88e5d4b65a8637b17723bbda3cce8";
+const SECP256K1_MULTISIG_CODE_HASH: &str =
+    "0x5c5069eb0857efc65e1bca0c07df34c31663b3622fd3876c876320fc9634e2a8";
+const ANYONE_CAN_PAY_MAINNET_CODE_HASH: &str =
+    "0xd369597ff47f29fbc0d47d2e3775370d1250b85140c670e4718af712983a2354";
+const ANYONE_CAN_PAY_TESTNET_CODE_HASH: &str =
+    "0x3419a1c09eb2567f6552ee7a8ecffd64155cffe0f1796e6e61ec088d740c1356";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AddressError {
+    /// Not bech32, or a checksum mismatch.
+    Encoding,
+    UnknownNetwork,
+    /// The payload doesn't match its format tag.
+    Payload,
+}
+
+impl std::fmt::Display for AddressError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Encoding => write!(f, "Not a valid bech32 address"),
+            Self::UnknownNetwork => write!(f, "Address must start with ckb1 or ckt1"),
+            Self::Payload => write!(f, "Malformed address payload"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum Network {
+    #[default]
+    Mainnet,
+    Testnet,
+}
+
+impl Network {
+    pub fn hrp(self) -> &'static str {
+        match self {
+            Self::Mainnet => "ckb",
+            Self::Testnet => "ckt",
+        }
+    }
+}
+
+/// The lock script of a CKB address, in any format (full, short or deprecated full).
+pub fn parse_address(address: &str) -> Result<(Network, Script), AddressError> {
+    let (hrp, data, variant) =
+        bech32::decode(address.trim()).map_err(|_| AddressError::Encoding)?;
+    let network = match hrp.as_str() {
+        "ckb" => Network::Mainnet,
+        "ckt" => Network::Testnet,
+        _ => return Err(AddressError::UnknownNetwork),
+    };
+    let payload = Vec::<u8>::from_base32(&data).map_err(|_| AddressError::Encoding)?;
+    let script = |code_hash: &[u8], hash_type: &str, args: &[u8]| Script {
+        code_hash: format!("0x{}", hex::encode(code_hash)),
+        hash_type: hash_type.to_owned(),
+        args: format!("0x{}", hex::encode(args)),
+    };
+    let script = match (payload.first(), variant) {
+        (Some(&FULL_FORMAT), Variant::Bech32m) if payload.len() >= 34 => {
+            let hash_type = hash_type_from_byte(payload[33]).map_err(|_| AddressError::Payload)?;
+            script(&payload[1..33], hash_type, &payload[34..])
+        }
+        (Some(&SHORT_FORMAT), Variant::Bech32) if payload.len() == 22 => {
+            let code_hash = match (payload[1], network) {
+                (0, _) => SECP256K1_BLAKE160_CODE_HASH,
+                (1, _) => SECP256K1_MULTISIG_CODE_HASH,
+                (2, Network::Mainnet) => ANYONE_CAN_PAY_MAINNET_CODE_HASH,
+                (2, Network::Testnet) => ANYONE_CAN_PAY_TESTNET_CODE_HASH,
+                _ => return Err(AddressError::Payload),
+            };
+            Script {
+                code_hash: code_hash.to_owned(),
+                hash_type: "type".to_owned(),
+                args: format!("0x{}", hex::encode(&payload[2..])),
+            }
+        }
+        (Some(&FULL_DATA_FORMAT), Variant::Bech32) if payload.len() >= 33 => {
+            script(&payload[1..33], "data", &payload[33..])
+        }
+        (Some(&FULL_TYPE_FORMAT), Variant::Bech32) if payload.len() >= 33 => {
+            script(&payload[1..33], "type", &payload[33..])
+        }
+        _ => return Err(AddressError::Payload),
+    };
+    Ok((network, script))
+}
+
+impl Script {
+    /// Full format (bech32m) address of this lock script.
+    pub fn to_address(&self, network: Network) -> Result<String, ScriptError> {
+        let code_hash =
+            hex::decode(strip_0x(&self.code_hash)).map_err(|_| ScriptError::CodeHashError)?;
+        if code_hash.len() != 32 {
+            return Err(ScriptError::CodeHashError);
+        }
+        let args = hex::decode(strip_0x(&self.args)).map_err(|_| ScriptError::ArgsError)?;
+
+        let mut payload = Vec::with_capacity(1 + 32 + 1 + args.len());
+        payload.push(FULL_FORMAT);
+        payload.extend_from_slice(&code_hash);
+        payload.push(hash_type_to_byte(&self.hash_type)?);
+        payload.extend_from_slice(&args);
+        // CKB addresses are longer than the 90 characters BIP-173 allows, which
+        // `bech32::encode` doesn't enforce.
+        Ok(
+            bech32::encode(network.hrp(), payload.to_base32(), Variant::Bech32m)
+                .expect("the hrp is valid"),
+        )
+    }
+}
@@ -1,19 +1,101 @@
+mod address_book;
+mod byte_map;
+mod compare;
+mod deep_link;
+mod drafts;
+mod history;
+mod order_book;
+mod pages;
+mod qr_view;
+mod send_tx;
+mod trade_history;
+mod watcher;
+#[cfg(target_arch = "wasm32")]
+mod web;
+mod workspace;
+
+use crate::dex::{
+    mode_description, parse_owner_script_hash, udt_amount, DexArgs, DexHelperError, MAX_MODE,
+};
+use crate::diagnose::{diagnose_args, normalize_args, Diagnostic, Severity};
+use crate::paste::{recognize, Candidate, Paste};
+use crate::rpc::{
+    format_ckb, DexCell, OutPoint, RpcClient, RpcError, Script, SearchKey, DEFAULT_RPC_URL,
+};
+use address_book::AddressBook;
+use byte_map::ByteMap;
+use compare::Compare;
+use deep_link::DeepLink;
+use drafts::{Draft, Drafts};
+use history::History;
+use order_book::{Offer, OrderBook};
+use pages::Page;
+use qr_view::QrView;
+use send_tx::SendTx;
+use trade_history::TradeHistory;
+use watcher::Watcher;
+use workspace::Workspace;
+
 /// We derive Deserialize/Serialize so we can persist app state on shutdown.
 #[derive(serde::Deserialize, serde::Serialize)]
 #[serde(default)] // if we add new fields, give them default values when deserializing old state
 pub struct TemplateApp {
     // Example stuff:
-    #[serde(skip)] // This how you opt-out of serialization of a field
     owner_script_hash: String,
     price_base: u32,
     price_pow: u32,
     mode: u16,
     amount: u64,
     encoded_string: String,
-    encode_status: String,
-    decode_status: String,
-    last_status: bool,
-    ckb_cap: f64,
+    /// Outcome of the last smart paste, until the next edit.
+    #[serde(skip)]
+    paste_notice: Option<Result<String, String>>,
+    #[serde(skip)]
+    decode_diagnostics: Vec<Diagnostic>,
+    /// Dex cells of a pasted transaction, to pick from.
+    #[serde(skip)]
+    paste_candidates: Vec<Candidate>,
+    rpc_url: String,
+    out_point_tx_hash: String,
+    out_point_index: u32,
+    #[serde(skip)]
+    dex_cell_fetch: Option<poll_promise::Promise<Result<DexCell, RpcError>>>,
+    #[serde(skip)]
+    dex_cell: Option<Result<DexCell, RpcError>>,
+    #[serde(skip)]
+    order_book: OrderBook,
+    /// Lock script of the seller, used to find their offers and to build cancel transactions.
+    my_lock: Script,
+    #[serde(skip)]
+    my_orders: OrderBook,
+    #[serde(skip)]
+    cancel_tx: String,
+    #[serde(skip)]
+    trade_history: TradeHistory,
+    #[serde(skip)]
+    send_tx: SendTx,
+    watcher: Watcher,
+    #[serde(skip)]
+    byte_map: ByteMap,
+    compare: Compare,
+    drafts: Drafts,
+    address_book: AddressBook,
+    #[serde(skip)]
+    workspace: Workspace,
+    #[serde(skip)]
+    history: History,
+    page: Page,
+    /// URL fragment the page was last synced with, on the web.
+    #[serde(skip)]
+    last_fragment: Option<String>,
+    /// What share links start with: the page URL on the web, `dex-helper://` natively.
+    #[serde(skip)]
+    share_base: String,
+    /// Outcome of opening a link, until dismissed.
+    #[serde(skip)]
+    link_notice: Option<Result<String, String>>,
+    #[serde(skip)]
+    qr: QrView,
 }
 
 impl Default for TemplateApp {
@@ -27,10 +109,35 @@ impl Default for TemplateApp {
             price_pow: 0,
             amount: 1,
             encoded_string: "0x".to_owned(),
-            encode_status: "".to_owned(),
-            decode_status: "".to_owned(),
-            last_status: true,
-            ckb_cap: 0.0,
+            paste_notice: None,
+            decode_diagnostics: vec![],
+            paste_candidates: vec![],
+            rpc_url: DEFAULT_RPC_URL.to_owned(),
+            out_point_tx_hash: "".to_owned(),
+            out_point_index: 0,
+            dex_cell_fetch: None,
+            dex_cell: None,
+            order_book: Default::default(),
+            my_lock: Script {
+                hash_type: "type".to_owned(),
+                ..Default::default()
+            },
+            my_orders: Default::default(),
+            cancel_tx: "".to_owned(),
+            trade_history: Default::default(),
+            send_tx: Default::default(),
+            watcher: Default::default(),
+            byte_map: Default::default(),
+            compare: Default::default(),
+            drafts: Default::default(),
+            address_book: Default::default(),
+            workspace: Default::default(),
+            history: Default::default(),
+            page: Default::default(),
+            last_fragment: None,
+            share_base: deep_link::SCHEME.to_owned(),
+            link_notice: None,
+            qr: Default::default(),
         }
     }
 }
@@ -43,62 +150,214 @@ impl TemplateApp {
 
         // Load previous app state (if any).
         // Note that you must enable the `persistence` feature for this to work.
-        if let Some(storage) = cc.storage {
-            return eframe::get_value(storage, eframe::APP_KEY).unwrap_or_default();
+        let mut app: Self = cc.storage.and_then(workspace::load).unwrap_or_default();
+        app.restore();
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            let location = &cc.integration_info.web_info.location;
+            app.share_base = deep_link::page_url(&location.url).to_owned();
+            app.last_fragment = Some(location.hash.clone());
+            if location.hash.len() > 1 {
+                app.open_link(&location.hash);
+            } else if !location.query.is_empty() {
+                app.open_link(&format!("?{}", location.query));
+            }
         }
+        // e.g. `dex-helper 'dex-helper://#decode=0x...'`
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(link) = std::env::args().nth(1) {
+            app.open_link(&link);
+        }
+        app
+    }
 
-        Default::default()
+    /// Open the order or page a link points to, in a new draft so the
+    /// current ones are left alone.
+    fn open_link(&mut self, link: &str) {
+        let link = match DeepLink::parse(link) {
+            Ok(link) => link,
+            Err(e) => {
+                self.link_notice = Some(Err(format!("Can't open the link: {e}")));
+                return;
+            }
+        };
+        if link.has_order() {
+            self.drafts.store(self.current_draft());
+            self.drafts.push("Shared order");
+            self.load_draft();
+            if let Some(args) = link.args {
+                self.encoded_string = args;
+                self.args_edited();
+                self.page = Page::Decode;
+            } else {
+                self.mode = link.mode.unwrap_or(self.mode);
+                self.owner_script_hash = link.owner.unwrap_or_default();
+                self.amount = link.amount.unwrap_or(self.amount);
+                self.price_base = link.price_base.unwrap_or(self.price_base);
+                self.price_pow = link.price_pow.unwrap_or(self.price_pow);
+                self.fields_edited();
+                self.page = Page::Encode;
+            }
+            self.link_notice = Some(Ok("Opened a shared order".to_owned()));
+        }
+        if let Some(page) = link.page {
+            self.page = page;
+        }
     }
 
-    fn decode(&mut self) -> Result<(), DexHelperError> {
-        let args_str = self
-            .encoded_string
-            .strip_prefix("0x")
-            .unwrap_or(&self.encoded_string);
-        let args = hex::decode(args_str).map_err(|_| DexHelperError::ArgsDecodeError)?;
-        if args.len() != 42 {
-            return Err(DexHelperError::ArgsLenError);
+    /// Fill the fields from the drafts after loading or importing state.
+    fn restore(&mut self) {
+        if self.drafts.is_empty() {
+            // a fresh start, the fields hold the example order
+            self.drafts.store(self.current_draft());
+        } else {
+            self.load_draft();
         }
-        let mode_bytes = [args[0], args[1]];
-        let mode = u16::from_le_bytes(mode_bytes);
-        if mode > 2 {
-            return Err(DexHelperError::ModeTooBig);
+        self.sync();
+    }
+
+    fn current_draft(&self) -> Draft {
+        Draft {
+            name: Default::default(),
+            mode: self.mode,
+            owner_script_hash: self.owner_script_hash.clone(),
+            amount: self.amount,
+            price_base: self.price_base,
+            price_pow: self.price_pow,
+            encoded_string: self.encoded_string.clone(),
         }
-        self.mode = mode;
-        let owner_script_hash = &args[2..34];
-        self.owner_script_hash = format!("0x{}", hex::encode(owner_script_hash));
-        let price_base_bytes = [args[34], args[35], args[36], args[37]];
-        self.price_base = u32::from_le_bytes(price_base_bytes);
-        let price_pow_bytes = [args[38], args[39], args[40], args[41]];
-        self.price_pow = u32::from_le_bytes(price_pow_bytes);
-        Ok(())
+    }
+
+    /// Fill the encoder fields from the active draft.
+    fn load_draft(&mut self) {
+        self.apply_draft(self.drafts.active().clone());
+        self.history.clear();
+    }
+
+    /// Set the order fields, e.g. to undo an edit.
+    fn apply_draft(&mut self, draft: Draft) {
+        self.mode = draft.mode;
+        self.owner_script_hash = draft.owner_script_hash;
+        self.amount = draft.amount;
+        self.price_base = draft.price_base;
+        self.price_pow = draft.price_pow;
+        self.encoded_string = draft.encoded_string;
+        self.paste_notice = None;
+        self.paste_candidates.clear();
+        self.decode_diagnostics = diagnose_args(&self.encoded_string);
+    }
+
+    /// Make the fields and the args agree, after loading state saved by an older version.
+    fn sync(&mut self) {
+        if DexArgs::decode(&self.encoded_string).is_ok() {
+            self.decode_args();
+        } else {
+            self.fields_edited();
+        }
+    }
+
+    /// Update the fields from the args box, when it holds valid args.
+    fn decode_args(&mut self) {
+        self.decode_diagnostics = diagnose_args(&self.encoded_string);
+        if let Ok(args) = DexArgs::decode(&self.encoded_string) {
+            self.set_args(&args);
+        }
+    }
+
+    /// The args box was edited: pick args out of a paste, or decode them.
+    fn args_edited(&mut self) {
+        self.paste_notice = None;
+        if !self.smart_paste() {
+            self.decode_args();
+        }
+    }
+
+    /// A field was edited: re-encode the args, unless the owner hash is invalid.
+    fn fields_edited(&mut self) {
+        self.paste_notice = None;
+        self.paste_candidates.clear();
+        if let Ok(encoded) = self.encode() {
+            self.encoded_string = encoded;
+            self.decode_diagnostics = diagnose_args(&self.encoded_string);
+        }
+    }
+
+    /// Replace a pasted script, address or transaction with the dex args in it.
+    ///
+    /// Returns whether the args box held one of those.
+    fn smart_paste(&mut self) -> bool {
+        self.paste_candidates.clear();
+        match recognize(&self.encoded_string) {
+            Paste::Raw => return false,
+            Paste::NoDexLock { kind } => {
+                self.decode_diagnostics.clear();
+                self.paste_notice = Some(Err(format!("This {kind} isn't locked by the dex lock")));
+            }
+            Paste::Found {
+                kind,
+                mut candidates,
+            } => {
+                if candidates.len() == 1 {
+                    self.pick_pasted(candidates.remove(0));
+                } else {
+                    self.decode_diagnostics.clear();
+                    self.paste_notice = Some(Ok(format!(
+                        "This {kind} has {} dex cells, pick one",
+                        candidates.len()
+                    )));
+                    self.paste_candidates = candidates;
+                }
+            }
+        }
+        true
+    }
+
+    fn pick_pasted(&mut self, candidate: Candidate) {
+        self.paste_candidates.clear();
+        self.encoded_string = candidate.args;
+        self.decode_args();
+        self.paste_notice = Some(Ok(format!("Args of the {}", candidate.label)));
+    }
+
+    fn set_args(&mut self, args: &DexArgs) {
+        self.mode = args.mode;
+        self.owner_script_hash = args.owner_script_hash_hex();
+        self.price_base = args.price_base;
+        self.price_pow = args.price_pow;
     }
 
     fn encode(&mut self) -> Result<String, DexHelperError> {
-        let mut encoded = "0x".to_owned();
-        let owner_script_hash_str = self
-            .owner_script_hash
-            .strip_prefix("0x")
-            .unwrap_or(self.owner_script_hash.as_str());
-        let owner_script_hash =
-            hex::decode(owner_script_hash_str).map_err(|_| DexHelperError::LockScriptHashError)?;
-        if owner_script_hash.len() != 32 {
-            return Err(DexHelperError::LockScriptHashError);
-        }
-        let mode = hex::encode(self.mode.to_le_bytes());
-        encoded.push_str(mode.as_str());
-        encoded.push_str(owner_script_hash_str);
-        encoded.push_str(hex::encode(self.price_base.to_le_bytes()).as_str());
-        encoded.push_str(hex::encode(self.price_pow.to_le_bytes()).as_str());
-
-        Ok(encoded)
+        let args = DexArgs {
+            mode: self.mode,
+            owner_script_hash: parse_owner_script_hash(&self.owner_script_hash)?,
+            price_base: self.price_base,
+            price_pow: self.price_pow,
+        };
+        Ok(args.encode())
+    }
+
+    /// Open a cell fetched over RPC in a new draft, like a shared link.
+    fn load_dex_cell(&mut self, cell: &DexCell) {
+        self.drafts.store(self.current_draft());
+        self.drafts.push(&format!("Cell {}", cell.out_point));
+        self.load_draft();
+        self.encoded_string = cell.output.lock.args.clone();
+        self.paste_notice = None;
+        self.paste_candidates.clear();
+        self.decode_args();
+        if cell.args.is_ok() {
+            if let Some(amount) = udt_amount(&cell.data_bytes()) {
+                self.amount = amount.clamp(1, u64::MAX as u128) as u64;
+            }
+        }
     }
 }
 
 impl eframe::App for TemplateApp {
     /// Called by the frame work to save state before shutdown.
     fn save(&mut self, storage: &mut dyn eframe::Storage) {
-        eframe::set_value(storage, eframe::APP_KEY, self);
+        workspace::save(self, storage);
     }
 
     /// Called each time the UI needs repainting, which may be many times per second.
@@ -106,197 +365,724 @@ impl eframe::App for TemplateApp {
         // Put your widgets into a `SidePanel`, `TopBottomPanel`, `CentralPanel`, `Window` or `Area`.
         // For inspiration and more examples, go to https://emilk.github.io/egui
 
+        self.watcher.poll(ctx, &self.rpc_url);
+        #[cfg(target_arch = "wasm32")]
+        {
+            // a link or back/forward changed the fragment
+            let hash = &_frame.info().web_info.location.hash;
+            if self.last_fragment.as_ref() != Some(hash) {
+                let hash = hash.clone();
+                self.open_link(&hash);
+                self.last_fragment = Some(hash);
+            }
+        }
+        self.workspace_ui(ctx);
+
+        // text fields have their own undo while focused
+        let editing_text = ctx
+            .memory(|m| m.focused())
+            .is_some_and(|id| egui::TextEdit::load_state(ctx, id).is_some());
+        if !editing_text {
+            let redo = egui::KeyboardShortcut::new(
+                egui::Modifiers::COMMAND | egui::Modifiers::SHIFT,
+                egui::Key::Z,
+            );
+            let undo = egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::Z);
+            // redo first, undo would match Ctrl+Shift+Z too
+            let restore = if ctx.input_mut(|i| i.consume_shortcut(&redo)) {
+                self.history.redo()
+            } else if ctx.input_mut(|i| i.consume_shortcut(&undo)) {
+                self.history.undo()
+            } else {
+                None
+            };
+            if let Some(draft) = restore {
+                self.apply_draft(draft);
+            }
+        }
+
         egui::TopBottomPanel::top("top_panel").show(ctx, |ui| {
             // The top panel is often a good place for a menu bar:
 
             egui::menu::bar(ui, |ui| {
                 // NOTE: no File->Quit on web pages!
                 let is_web = cfg!(target_arch = "wasm32");
-                if !is_web {
-                    ui.menu_button("File", |ui| {
-                        if ui.button("Quit").clicked() {
-                            ctx.send_viewport_cmd(egui::ViewportCommand::Close);
-                        }
-                    });
-                    ui.add_space(16.0);
-                }
+                ui.menu_button("File", |ui| {
+                    self.workspace_menu(ui);
+                    if !is_web && ui.button("Quit").clicked() {
+                        ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                    }
+                });
+                ui.add_space(16.0);
 
                 egui::widgets::global_dark_light_mode_buttons(ui);
             });
         });
 
+        egui::SidePanel::left("pages")
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.heading("Dex Helper");
+                ui.separator();
+                if self.page.nav_ui(ui) {
+                    #[cfg(target_arch = "wasm32")]
+                    pages::set_fragment(self.page);
+                }
+                ui.with_layout(egui::Layout::bottom_up(egui::Align::LEFT), |ui| {
+                    powered_by_egui_and_eframe(ui);
+                });
+            });
+
         egui::CentralPanel::default().show(ctx, |ui| {
+            self.watcher.banner_ui(ui);
+            if let Some(notice) = &self.link_notice {
+                let dismiss = ui
+                    .horizontal(|ui| {
+                        match notice {
+                            Ok(notice) => ui.colored_label(egui::Color32::GREEN, notice),
+                            Err(e) => ui.colored_label(egui::Color32::RED, e),
+                        };
+                        ui.button("Dismiss").clicked()
+                    })
+                    .inner;
+                if dismiss {
+                    self.link_notice = None;
+                }
+            }
 
-        egui::ScrollArea::vertical().show(ui, |ui| {
+            egui::ScrollArea::vertical()
+                .id_source(self.page)
+                .show(ui, |ui| match self.page {
+                    Page::Encode => encode_page(ui, self),
+                    Page::Decode => decode_page(ui, self),
+                    Page::Contract => current_contract_info(ui, &self.encoded_string),
+                    Page::BuildTx => build_tx_page(ui, self),
+                    Page::Inspect => inspect_page(ui, self),
+                    Page::OrderBook => order_book_page(ui, self),
+                });
+        });
 
-            ui.horizontal(|ui| {
-                ui.vertical(|ui| {
-                    // The central panel the region left after adding TopPanel's and SidePanel's
-                    ui.heading("Dex Lock Args Encode/Decode Helper");
+        let time = ctx.input(|i| i.time);
+        self.history.record(self.current_draft(), time);
+        self.drafts.store(self.current_draft());
+    }
+}
 
-                    ui.horizontal(|ui| {
-                        ui.label("Mode");
-                        ui.add(egui::widgets::Slider::new(&mut self.mode, 0..=2));
-                        match self.mode {
-                            0 => {
-                                ui.label(egui::RichText::new("UDT compatible mode").color(egui::Color32::YELLOW).background_color(egui::Color32::GRAY));
-                            }
-                            1 => {
-                                ui.label(egui::RichText::new("Restrict Mode, Can't modify Data/Type during trade.").color(egui::Color32::YELLOW).background_color(egui::Color32::GRAY));
-                            }
-                            2 => {
-                                ui.label(egui::RichText::new("Partial Restrict Mode, Can't modify Type during trade, but Data can.").color(egui::Color32::YELLOW).background_color(egui::Color32::GRAY));
-                            }
-                            _ => {}
-                        }
-                    });
+fn encode_page(ui: &mut egui::Ui, app: &mut TemplateApp) {
+    ui.heading("Dex Lock Args Encoder");
+    if app.drafts.ui(ui) {
+        app.load_draft();
+        app.sync();
+    }
+    egui::CollapsingHeader::new("Edit History").show(ui, |ui| {
+        if let Some(draft) = app.history.ui(ui) {
+            app.apply_draft(draft);
+        }
+    });
 
-                    if self.mode == 0 {
-                        ui.horizontal(|ui| {
-                            ui.label("Amount");
-                            ui.add(egui::widgets::Slider::new(&mut self.amount, 1..=u64::MAX));
-                            ui.separator();
-                            ui.label("Data: ");
-                            let fixed = (self.amount as u128).to_le_bytes();
-                            let data = hex::encode(fixed);
-
-                            ui.label(egui::RichText::new(format!("0x{}", data)));
-                        });
-                    }
+    let mut fields_changed = false;
+    ui.horizontal(|ui| {
+        ui.label("Mode");
+        fields_changed |= ui
+            .add(egui::widgets::Slider::new(&mut app.mode, 0..=MAX_MODE))
+            .changed();
+        ui.label(
+            egui::RichText::new(mode_description(app.mode))
+                .color(egui::Color32::YELLOW)
+                .background_color(egui::Color32::GRAY),
+        );
+    });
 
-                    ui.horizontal(|ui| {
-                        ui.label("Owner LockScript Hash");
-                        ui.add_sized(
-                            ui.available_size() / 2.5,
-                            egui::TextEdit::singleline(&mut self.owner_script_hash),
-                        );
-                    });
+    if app.mode == 0 {
+        ui.horizontal(|ui| {
+            ui.label("Amount");
+            ui.add(egui::widgets::Slider::new(&mut app.amount, 1..=u64::MAX));
+            ui.separator();
+            ui.label("Data: ");
+            let fixed = (app.amount as u128).to_le_bytes();
+            let data = hex::encode(fixed);
 
-                    ui.horizontal(|ui| {
-                        if ui.button("Encode").clicked() {
-                            match self.encode() {
-                                Ok(encoded) => {
-                                    self.encoded_string = encoded;
-                                    self.encode_status = "".to_string();
-                                }
-                                Err(e) => match e {
-                                    DexHelperError::LockScriptHashError => {
-                                        self.encode_status =
-                                            "LockScript Hash Len Error!!!Must Be 32 bytes!"
-                                                .to_string();
-                                    },
-                                    DexHelperError::ArgsDecodeError => {
-                                        self.encode_status = "Args Decode Error!!!".to_string();
-                                    },
-                                    _ => {
-                                        unreachable!();
-                                    }
-                                },
-                            }
-                        }
-                        if ui.button("Copy").clicked() {
-                            ui.output_mut(|o| {
-                                o.copied_text = self.owner_script_hash.clone();
-                            });
-                            self.encode_status = "Owner Script Hash Copied".to_string();
-                        }
-                    });
+            ui.label(egui::RichText::new(format!("0x{}", data)));
+        });
+    }
 
-                    ui.label(egui::RichText::new(&self.encode_status).color(
-                        if self.encode_status.is_empty() {
-                            egui::Color32::WHITE
-                        } else {
-                            egui::Color32::RED
-                        },
-                    ));
-                    ui.label("Price Base:");
-                    ui.add(egui::widgets::Slider::new(
-                        &mut self.price_base,
-                        1..=u32::MAX,
-                    ));
-                    ui.label("Price Pow:");
-                    ui.add(egui::widgets::Slider::new(
-                        &mut self.price_pow,
-                        0..=15,
-                    ));
-                });
+    let owner_error = parse_owner_script_hash(&app.owner_script_hash).err();
+    ui.horizontal(|ui| {
+        ui.label("Owner LockScript Hash");
+        let mut owner = egui::TextEdit::singleline(&mut app.owner_script_hash);
+        if owner_error.is_some() {
+            owner = owner.text_color(egui::Color32::RED);
+        }
+        fields_changed |= ui.add_sized(ui.available_size() / 2.5, owner).changed();
+        if ui.button("Copy").clicked() {
+            ui.output_mut(|o| {
+                o.copied_text = app.owner_script_hash.clone();
+            });
+        }
+        fields_changed |= app.address_book.pick_ui(ui, &mut app.owner_script_hash);
+        if let Some(label) = app.address_book.label(&app.owner_script_hash) {
+            ui.label(egui::RichText::new(label).strong());
+        }
+    });
+    if let Some(e) = owner_error {
+        ui.label(
+            egui::RichText::new(format!("{e}, the args aren't updated")).color(egui::Color32::RED),
+        );
+    }
+
+    ui.label("Price Base:");
+    fields_changed |= ui
+        .add(
+            // decoded args may hold 0, show it as is rather than clamping it away
+            egui::widgets::Slider::new(&mut app.price_base, 1..=u32::MAX).clamp_to_range(false),
+        )
+        .changed();
+    if app.price_base == 0 {
+        ui.label(
+            egui::RichText::new("The price is 0, anyone can take this order for free")
+                .color(egui::Color32::LIGHT_YELLOW),
+        );
+    }
+    ui.label("Price Pow:");
+    fields_changed |= ui
+        // like the base, a bigger pow from decoded args isn't clamped away
+        .add(egui::widgets::Slider::new(&mut app.price_pow, 0..=15).clamp_to_range(false))
+        .changed();
+    if app.price_pow > 15 {
+        ui.label(
+            egui::RichText::new(format!(
+                "Price Pow {} is above 15, check the total price isn't far more than intended",
+                app.price_pow
+            ))
+            .color(egui::Color32::LIGHT_YELLOW),
+        );
+    }
+    if fields_changed {
+        app.fields_edited();
+    }
+    ui.separator();
+    ui.horizontal(|ui| {
+        ui.heading("Encoded Args");
+        ui.label(egui::RichText::new(&app.encoded_string).monospace());
+        if ui.button("Copy").clicked() {
+            ui.output_mut(|o| {
+                o.copied_text = app.encoded_string.clone();
+            });
+        }
+        share_link_button(ui, app);
+    });
+    // next to the fields, so editing one highlights the bytes it changed
+    byte_map(ui, app);
+    ui.separator();
+    current_encode_method(ui, app);
+    ui.separator();
+    ui.heading("Address Book");
+    app.address_book.ui(ui);
+}
+
+fn decode_page(ui: &mut egui::Ui, app: &mut TemplateApp) {
+    ui.heading("Dex Lock Args Decoder");
+    ui.horizontal(|ui| {
+        ui.heading("Encoded Args");
+        let mut layouter = |ui: &egui::Ui, text: &str, wrap_width: f32| {
+            let mut job = args_layout_job(ui, text);
+            job.wrap.max_width = wrap_width;
+            ui.fonts(|f| f.layout_job(job))
+        };
+        let response = ui.add_sized(
+            [ui.available_width() - 50.0, ui.spacing().interact_size.y],
+            egui::TextEdit::singleline(&mut app.encoded_string)
+                .hint_text("args, lock script, address or transaction JSON")
+                .layouter(&mut layouter),
+        );
+        if response.changed() {
+            app.args_edited();
+        }
+        if ui.button("Copy").clicked() {
+            ui.output_mut(|o| {
+                o.copied_text = app.encoded_string.clone();
+            });
+        }
+    });
+
+    match &app.paste_notice {
+        Some(Ok(notice)) => {
+            ui.label(egui::RichText::new(notice).color(egui::Color32::GREEN));
+        }
+        Some(Err(notice)) => {
+            ui.label(egui::RichText::new(notice).color(egui::Color32::RED));
+        }
+        None => {}
+    }
+    let mut picked = None;
+    for candidate in &app.paste_candidates {
+        if ui
+            .button(&candidate.label)
+            .on_hover_text(&candidate.args)
+            .clicked()
+        {
+            picked = Some(candidate.clone());
+        }
+    }
+    if let Some(candidate) = picked {
+        app.pick_pasted(candidate);
+    }
+    if diagnostics_ui(ui, &app.decode_diagnostics) {
+        app.encoded_string = normalize_args(&app.encoded_string);
+        app.args_edited();
+    }
+    share_link_button(ui, app);
+    ui.separator();
+    byte_map(ui, app);
+}
+
+fn byte_map(ui: &mut egui::Ui, app: &mut TemplateApp) {
+    ui.heading("Byte Map");
+    match DexArgs::decode(&app.encoded_string) {
+        Ok(args) => app.byte_map.ui(ui, &args, &app.address_book),
+        Err(_) => {
+            ui.label(
+                egui::RichText::new("The encoded args don't decode")
+                    .color(egui::Color32::PLACEHOLDER),
+            );
+        }
+    }
+}
+
+fn share_link_button(ui: &mut egui::Ui, app: &TemplateApp) {
+    let valid = DexArgs::decode(&app.encoded_string).is_ok();
+    if ui
+        .add_enabled(valid, egui::Button::new("Copy Share Link"))
+        .on_hover_text("A link opening this order in the decoder")
+        .on_disabled_hover_text("The args don't decode")
+        .clicked()
+    {
+        let link = deep_link::share_link(&app.share_base, &app.encoded_string);
+        ui.output_mut(|o| o.copied_text = link);
+    }
+}
+
+/// Total price of the order in the args box, e.g. `100 CKB`.
+fn total_price_ckb(app: &TemplateApp) -> String {
+    let total = DexArgs::decode(&app.encoded_string)
+        .and_then(|args| args.checked_total_price(app.amount as u128));
+    match total {
+        Ok(total) => format!("{} CKB", format_ckb(total)),
+        Err(e) => format!("<{e}>"),
+    }
+}
+
+fn build_tx_page(ui: &mut egui::Ui, app: &mut TemplateApp) {
+    how_to_build_transaction(ui, app);
+    ui.separator();
+    ui.heading("Send Transaction");
+    app.send_tx.ui(ui, &app.rpc_url);
+    ui.separator();
+    ui.heading("QR Code");
+    app.qr.ui(ui, &app.encoded_string, &app.cancel_tx);
+}
+
+fn inspect_page(ui: &mut egui::Ui, app: &mut TemplateApp) {
+    fetch_dex_cell(ui, app);
+    ui.separator();
+    ui.horizontal(|ui| {
+        ui.heading("Compare Args");
+        if ui
+            .button("Use Encoded Args")
+            .on_hover_text("Put the encoded args on the left")
+            .clicked()
+        {
+            app.compare.set_left(&app.encoded_string);
+        }
+    });
+    app.compare.ui(ui, &app.address_book);
+    ui.separator();
+    ui.heading("Watched Orders");
+    app.watcher.ui(ui);
+    ui.separator();
+    trade_history(ui, app);
+}
+
+fn order_book_page(ui: &mut egui::Ui, app: &mut TemplateApp) {
+    order_book(ui, app);
+    ui.separator();
+    my_orders(ui, app);
+}
+
+fn severity_color(severity: Severity) -> egui::Color32 {
+    match severity {
+        Severity::Error => egui::Color32::RED,
+        Severity::Warning => egui::Color32::LIGHT_YELLOW,
+        Severity::Hint => egui::Color32::LIGHT_BLUE,
+    }
+}
+
+/// The args box text, red when it doesn't decode, with the chars diagnostics
+/// are about highlighted.
+fn args_layout_job(ui: &egui::Ui, input: &str) -> egui::text::LayoutJob {
+    let diagnostics = diagnose_args(input);
+    let text_color = if DexArgs::decode(input).is_ok() {
+        ui.visuals().text_color()
+    } else {
+        egui::Color32::RED
+    };
+    let font_id = egui::TextStyle::Body.resolve(ui.style());
+    let flush = |job: &mut egui::text::LayoutJob, run: &mut String, severity: Option<Severity>| {
+        let mut format = egui::TextFormat::simple(font_id.clone(), text_color);
+        if let Some(severity) = severity {
+            format.background = severity_color(severity).gamma_multiply(0.5);
+        }
+        job.append(&std::mem::take(run), 0.0, format);
+    };
+
+    let mut job = egui::text::LayoutJob::default();
+    let mut run = String::new();
+    let mut run_severity = None;
+    for (i, c) in input.chars().enumerate() {
+        let severity = diagnostics
+            .iter()
+            .find(|d| d.span.as_ref().is_some_and(|span| span.contains(&i)))
+            .map(|d| d.severity);
+        if severity != run_severity && !run.is_empty() {
+            flush(&mut job, &mut run, run_severity);
+        }
+        run_severity = severity;
+        run.push(c);
+    }
+    flush(&mut job, &mut run, run_severity);
+    job
+}
+
+/// What's wrong with the args, returns whether "Clean Up" was clicked.
+fn diagnostics_ui(ui: &mut egui::Ui, diagnostics: &[Diagnostic]) -> bool {
+    for diagnostic in diagnostics {
+        ui.label(
+            egui::RichText::new(&diagnostic.message).color(severity_color(diagnostic.severity)),
+        );
+    }
+    diagnostics.iter().any(|d| d.fixable)
+        && ui
+            .button("Clean Up")
+            .on_hover_text("Remove quotes and whitespace, fix the 0x prefix")
+            .clicked()
+}
+
+fn fetch_dex_cell(ui: &mut egui::Ui, app: &mut TemplateApp) {
+    ui.heading("Fetch Dex Cell From Chain");
+    ui.horizontal(|ui| {
+        ui.label("RPC Endpoint");
+        ui.text_edit_singleline(&mut app.rpc_url);
+    });
+    ui.horizontal(|ui| {
+        ui.label("OutPoint tx_hash");
+        ui.add_sized(
+            [ui.available_width() / 1.5, 18.0],
+            egui::TextEdit::singleline(&mut app.out_point_tx_hash).hint_text("0x..."),
+        );
+        ui.label("index");
+        ui.add(egui::DragValue::new(&mut app.out_point_index));
+
+        if let Some(promise) = app.dex_cell_fetch.take() {
+            match promise.try_take() {
+                Ok(result) => {
+                    if let Ok(cell) = &result {
+                        app.load_dex_cell(cell);
+                    }
+                    app.dex_cell = Some(result);
+                }
+                Err(promise) => app.dex_cell_fetch = Some(promise),
+            }
+        }
+        let fetching = app.dex_cell_fetch.is_some();
+        if ui
+            .add_enabled(!fetching, egui::Button::new("Fetch"))
+            .on_hover_text(
+                "Call get_live_cell (or get_transaction for dead cells) and decode the args",
+            )
+            .clicked()
+        {
+            let (sender, promise) = poll_promise::Promise::new();
+            let ctx = ui.ctx().clone();
+            let out_point = OutPoint::new(&app.out_point_tx_hash, app.out_point_index);
+            RpcClient::new(app.rpc_url.trim()).fetch_dex_cell(out_point, move |result| {
+                sender.send(result);
+                ctx.request_repaint();
+            });
+            app.dex_cell_fetch = Some(promise);
+        }
+        if fetching {
+            ui.spinner();
+        }
+    });
+
+    let Some(result) = &app.dex_cell else {
+        return;
+    };
+    match result {
+        Err(e) => {
+            ui.label(egui::RichText::new(e.to_string()).color(egui::Color32::RED));
+        }
+        Ok(cell) => {
+            ui.horizontal(|ui| {
+                ui.label("Status:");
+                ui.label(egui::RichText::new(&cell.status).color(if cell.is_live() {
+                    egui::Color32::GREEN
+                } else {
+                    egui::Color32::RED
+                }));
                 ui.separator();
-                ui.vertical(|ui| {
-                    ui.heading("Output");
+                ui.label("Capacity:");
+                let capacity = cell
+                    .output
+                    .capacity()
+                    .map(|c| format!("{} CKB", format_ckb(c)))
+                    .unwrap_or_else(|| cell.output.capacity.clone());
+                ui.label(egui::RichText::new(capacity).color(egui::Color32::LIGHT_GREEN));
+            });
+            match &cell.output.type_ {
+                Some(type_) => {
+                    ui.label(format!(
+                        "Type: codeHash: {} hashType: {} args: {}",
+                        type_.code_hash, type_.hash_type, type_.args
+                    ));
+                }
+                None => {
+                    ui.label("Type: None");
+                }
+            }
+            ui.label(format!("Data: {}", cell.data));
+            match &cell.args {
+                Ok(args) => {
                     ui.horizontal(|ui| {
-                        ui.heading("Encoded Args");
-                        ui.add_sized(
-                            ui.available_size(),
-                            egui::TextEdit::singleline(&mut self.encoded_string),
-                        );
+                        ui.label("Owner:");
+                        app.address_book.owner_ui(ui, &args.owner_script_hash_hex());
                     });
-
-                    let color = if self.last_status {
-                        egui::Color32::GREEN
-                    } else {
-                        egui::Color32::RED
-                    };
                     ui.horizontal(|ui| {
+                        ui.label(
+                            egui::RichText::new(
+                                "Dex args decoded, the order fields are filled from this cell",
+                            )
+                            .color(egui::Color32::GREEN),
+                        );
+                        if ui.button("Open in Encoder").clicked() {
+                            app.page = Page::Encode;
+                            #[cfg(target_arch = "wasm32")]
+                            pages::set_fragment(app.page);
+                        }
+                        let watching = app.watcher.is_watching(&cell.out_point);
                         if ui
-                            .button("Decode")
-                            .on_hover_text("Decode the encoded args")
+                            .add_enabled(cell.is_live() && !watching, egui::Button::new("Watch"))
+                            .on_hover_text("Get notified when this order is taken or cancelled")
                             .clicked()
                         {
-                            let result: Result<(), DexHelperError> = self.decode();
-                            self.last_status = result.is_ok();
-
-                            if result.is_err() {
-                                self.decode_status = "Decode Error!!!".to_string();
-                                match result.unwrap_err() {
-                                    DexHelperError::ModeTooBig => {
-                                        self.decode_status.push_str("Mode too big!");
-                                    }
-                                    DexHelperError::ArgsLenError => {
-                                        self.decode_status.push_str(
-                                            format!(
-                                                "Args Len Error!!!Must Be 42 bytes, but got {}",
-                                                self.encoded_string
-                                                    .strip_prefix("0x")
-                                                    .unwrap_or(self.encoded_string.as_str())
-                                                    .len()
-                                            )
-                                            .as_str(),
-                                        );
-                                    }
-                                    _ => {}
-                                }
-                            } else {
-                                self.decode_status = "Okay".to_string();
-                            }
-                        }
-
-                        if ui.button("Copy").clicked() {
-                            ui.output_mut(|o| {
-                                o.copied_text = self.encoded_string.clone();
-                            });
-                            self.decode_status = "Copied".to_string();
+                            app.watcher
+                                .watch(cell.out_point.clone(), cell.output.lock.args.clone());
                         }
                     });
+                }
+                Err(e) => {
+                    ui.label(
+                        egui::RichText::new(format!(
+                            "Lock args are not valid dex args: {e:?} (lock codeHash: {})",
+                            cell.output.lock.code_hash
+                        ))
+                        .color(egui::Color32::RED),
+                    );
+                }
+            }
+        }
+    }
+}
 
-                    ui.label(egui::RichText::new(&self.decode_status).color(color));
-                })
+fn order_book(ui: &mut egui::Ui, app: &mut TemplateApp) {
+    ui.horizontal(|ui| {
+        ui.heading("Order Book");
+        if ui
+            .add_enabled(!app.order_book.is_fetching(), egui::Button::new("Refresh"))
+            .on_hover_text("Query the indexer for all live dex cells")
+            .clicked()
+        {
+            app.order_book.reset(vec![SearchKey::dex_lock("0x")]);
+            app.order_book.fetch_next_page(ui.ctx(), &app.rpc_url);
+        }
+    });
+    if let Some(offer) = app
+        .order_book
+        .ui(ui, &app.rpc_url, &app.address_book, |_, _| {})
+    {
+        app.load_dex_cell(&offer.cell);
+    }
+}
+
+fn my_orders(ui: &mut egui::Ui, app: &mut TemplateApp) {
+    ui.heading("My Orders");
+    ui.horizontal(|ui| {
+        ui.label("Owner Lock codeHash");
+        ui.add_sized(
+            [ui.available_width() / 2.0, 18.0],
+            egui::TextEdit::singleline(&mut app.my_lock.code_hash).hint_text("0x..."),
+        );
+        egui::ComboBox::from_id_source("my_lock_hash_type")
+            .selected_text(&app.my_lock.hash_type)
+            .show_ui(ui, |ui| {
+                for hash_type in ["type", "data", "data1", "data2"] {
+                    ui.selectable_value(
+                        &mut app.my_lock.hash_type,
+                        hash_type.to_owned(),
+                        hash_type,
+                    );
+                }
             });
-            ui.separator();
-            current_contract_info(ui, &self.encoded_string);
-            ui.separator();
-            current_encode_method(ui, self);
-            ui.separator();
-            how_to_build_transaction(ui, self);
-            powered_by_egui_and_eframe(ui);
+    });
+    ui.horizontal(|ui| {
+        ui.label("Owner Lock args");
+        ui.add_sized(
+            [ui.available_width() / 1.5, 18.0],
+            egui::TextEdit::singleline(&mut app.my_lock.args).hint_text("0x..."),
+        );
+        app.address_book.pick_script_ui(ui, &mut app.my_lock);
+    });
+
+    // Without a lock script we can still search by the hash typed in the encoder,
+    // but can't build a cancel transaction.
+    let my_lock_hash = app.my_lock.calc_script_hash().ok();
+    ui.horizontal(|ui| {
+        ui.label("Owner LockScript Hash:");
+        match my_lock_hash {
+            Some(hash) => {
+                let hash = format!("0x{}", hex::encode(hash));
+                ui.label(egui::RichText::new(&hash).color(egui::Color32::LIGHT_GREEN));
+                if ui
+                    .button("Use in Encoder")
+                    .on_hover_text("Set the encoder's owner hash to this lock's hash")
+                    .clicked()
+                {
+                    app.owner_script_hash = hash;
+                    app.fields_edited();
+                }
+            }
+            None => {
+                ui.label(
+                    egui::RichText::new(format!(
+                        "{} (from encoder, enter the lock script to build cancel transactions)",
+                        app.owner_script_hash
+                    ))
+                    .color(egui::Color32::LIGHT_YELLOW),
+                );
+            }
+        }
+    });
+
+    let owner_hash =
+        my_lock_hash.map_or_else(|| parse_owner_script_hash(&app.owner_script_hash), Ok);
+    ui.horizontal(|ui| {
+        if ui
+            .add_enabled(
+                owner_hash.is_ok() && !app.my_orders.is_fetching(),
+                egui::Button::new("Search"),
+            )
+            .on_hover_text("Query the indexer for dex cells owned by this lock in every mode")
+            .clicked()
+        {
+            if let Ok(owner_hash) = owner_hash {
+                let search_keys = (0..=MAX_MODE)
+                    .map(|mode| {
+                        let prefix = format!(
+                            "0x{}{}",
+                            hex::encode(mode.to_le_bytes()),
+                            hex::encode(owner_hash)
+                        );
+                        SearchKey::dex_lock(&prefix)
+                    })
+                    .collect();
+                app.my_orders.reset(search_keys);
+                app.my_orders.fetch_next_page(ui.ctx(), &app.rpc_url);
+            }
+        }
+        if owner_hash.is_err() {
+            ui.label(
+                egui::RichText::new("Owner LockScript Hash is invalid").color(egui::Color32::RED),
+            );
+        }
+    });
+
+    let mut cancel: Option<Offer> = None;
+    let mut watch: Option<Offer> = None;
+    let picked = app
+        .my_orders
+        .ui(ui, &app.rpc_url, &app.address_book, |ui, offer| {
+            if ui
+                .add_enabled(my_lock_hash.is_some(), egui::Button::new("Cancel Tx"))
+                .on_hover_text("Generate a transaction returning this asset to the owner lock")
+                .on_disabled_hover_text("Enter the owner lock script above first")
+                .clicked()
+            {
+                cancel = Some(offer.clone());
+            }
+            if ui
+                .add_enabled(
+                    !app.watcher.is_watching(&offer.cell.out_point),
+                    egui::Button::new("Watch"),
+                )
+                .on_hover_text("Get notified when this order is taken or cancelled")
+                .clicked()
+            {
+                watch = Some(offer.clone());
+            }
         });
+    if let Some(offer) = watch {
+        app.watcher
+            .watch(offer.cell.out_point, offer.cell.output.lock.args);
+    }
+    if let Some(offer) = picked {
+        app.load_dex_cell(&offer.cell);
+    }
+    if let Some(offer) = cancel {
+        let tx = crate::tx::cancel_offer(&offer.cell, &app.my_lock);
+        app.cancel_tx = serde_json::to_string_pretty(&tx).expect("a transaction always serializes");
+    }
+
+    if !app.cancel_tx.is_empty() {
+        ui.horizontal(|ui| {
+            ui.label(egui::RichText::new("Cancel Transaction:").color(egui::Color32::LIGHT_YELLOW));
+            if ui.button("Copy").clicked() {
+                ui.output_mut(|o| o.copied_text = app.cancel_tx.clone());
+            }
+            if ui.button("Clear").clicked() {
+                app.cancel_tx.clear();
+            }
         });
+        ui.label(
+            egui::RichText::new(
+                "Add one of the owner's cells as input (it proves ownership and pays the fee), \
+                 its lock's cell dep and a witness, then sign.",
+            )
+            .color(egui::Color32::PLACEHOLDER),
+        );
+        ui.add(
+            egui::TextEdit::multiline(&mut app.cancel_tx.as_str())
+                .code_editor()
+                .desired_width(f32::INFINITY),
+        );
     }
 }
-enum DexHelperError {
-    ArgsDecodeError,
-    LockScriptHashError,
-    ModeTooBig,
-    ArgsLenError,
+
+fn trade_history(ui: &mut egui::Ui, app: &mut TemplateApp) {
+    ui.horizontal(|ui| {
+        ui.heading("Trade History");
+        if ui
+            .add_enabled(
+                !app.trade_history.is_fetching(),
+                egui::Button::new("Refresh"),
+            )
+            .on_hover_text("Scan transactions consuming dex cells, newest first")
+            .clicked()
+        {
+            app.trade_history.reset();
+            app.trade_history.fetch_next_page(ui.ctx(), &app.rpc_url);
+        }
+    });
+    app.trade_history.ui(ui, &app.rpc_url, &app.address_book);
 }
 
 fn current_encode_method(ui: &mut egui::Ui, app: &mut TemplateApp) {
@@ -349,6 +1135,9 @@ fn current_encode_method(ui: &mut egui::Ui, app: &mut TemplateApp) {
 
         ui.spacing_mut().item_spacing.x = original_space;
         ui.separator();
+        if let Some(label) = app.address_book.label(&app.owner_script_hash) {
+            ui.label(egui::RichText::new(label).strong());
+        }
         if ui
             .label(
                 egui::RichText::new(app.owner_script_hash.clone())
@@ -421,8 +1210,6 @@ fn current_encode_method(ui: &mut egui::Ui, app: &mut TemplateApp) {
             }
             _ => app.price_base as f64 * 10f64.powf(app.price_pow as f64),
         };
-        app.ckb_cap = total / 1000_0000.0;
-
         if app.mode == 0 {
             ui.label(egui::RichText::new(format!(
                 "{} * {} * 10^{} / 10 ^ 8 = {} Shannons",
@@ -435,7 +1222,7 @@ fn current_encode_method(ui: &mut egui::Ui, app: &mut TemplateApp) {
             )));
         }
         ui.separator();
-        ui.label(egui::RichText::new(format!("{} CKB", app.ckb_cap)));
+        ui.label(total_price_ckb(app));
         if total < 1.0 {
             ui.label("|");
             ui.label(
@@ -561,7 +1348,7 @@ fn how_to_build_transaction(ui: &mut egui::Ui, app: &TemplateApp) {
                         ui.label(egui::RichText::new(format!("    - Data: 0x{}", hex::encode((app.amount as u128).to_le_bytes()))).color(egui::Color32::LIGHT_YELLOW));
                     }
                     ui.label(egui::RichText::new("    - Type: <USER_DEFINED>").color(egui::Color32::LIGHT_YELLOW));
-                    ui.label(egui::RichText::new(format!("    - Lock: <USER_DEFINED> (Lock.hash = {})", app.owner_script_hash)).color(egui::Color32::LIGHT_YELLOW));
+                    ui.label(egui::RichText::new(format!("    - Lock: <USER_DEFINED> (Lock.hash = {})", app.address_book.describe(&app.owner_script_hash))).color(egui::Color32::LIGHT_YELLOW));
                     ui.label(egui::RichText::new("  <Other Cells...>").color(egui::Color32::LIGHT_GREEN));
                 });
                 ui.separator();
@@ -606,9 +1393,9 @@ fn how_to_build_transaction(ui: &mut egui::Ui, app: &TemplateApp) {
                     ui.label(egui::RichText::new("    - Type: <USER_DEFINED>  (Should be same with original)").color(egui::Color32::LIGHT_YELLOW));
                     ui.label(egui::RichText::new("    - Lock: <USER_DEFINED> (Buyer's lock)").color(egui::Color32::LIGHT_YELLOW));
                     ui.label(egui::RichText::new("  Orignal Owner Peyment Receive Cell:").color(egui::Color32::LIGHT_GREEN));
-                    ui.label(egui::RichText::new(format!("    - Capacity: N + {} CKB", app.ckb_cap)).color(egui::Color32::GREEN));
+                    ui.label(egui::RichText::new(format!("    - Capacity: N + {}", total_price_ckb(app))).color(egui::Color32::GREEN));
                     ui.label(egui::RichText::new("    - Type: <USER_DEFINED>").color(egui::Color32::LIGHT_YELLOW));
-                    ui.label(egui::RichText::new(format!("    - Lock: <USER_DEFINED> (Lock.hash = {})", app.owner_script_hash)).color(egui::Color32::LIGHT_YELLOW));
+                    ui.label(egui::RichText::new(format!("    - Lock: <USER_DEFINED> (Lock.hash = {})", app.address_book.describe(&app.owner_script_hash))).color(egui::Color32::LIGHT_YELLOW));
                 });
             });
             ui.separator();
@@ -0,0 +1,204 @@
+use super::order_book::copyable;
+use crate::address::parse_address;
+use crate::dex::{parse_owner_script_hash, strip_0x};
+use crate::rpc::Script;
+
+/// A labeled owner lock, known by its address, its script or only its hash.
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+pub struct Owner {
+    pub label: String,
+    pub address: Option<String>,
+    pub script: Option<Script>,
+    /// `0x` prefixed, lowercase.
+    pub lock_hash: String,
+}
+
+impl Owner {
+    /// Parse an address, a lock script as JSON or a bare lock hash.
+    fn parse(label: &str, input: &str) -> Result<Self, String> {
+        let input = input.trim();
+        let (address, script) = if input.starts_with("ckb1") || input.starts_with("ckt1") {
+            let (_, script) = parse_address(input).map_err(|e| e.to_string())?;
+            (Some(input.to_owned()), Some(script))
+        } else if input.starts_with('{') {
+            let script: Script =
+                serde_json::from_str(input).map_err(|e| format!("Not a lock script: {e}"))?;
+            (None, Some(script))
+        } else {
+            (None, None)
+        };
+        let lock_hash = match &script {
+            Some(script) => script
+                .calc_script_hash_hex()
+                .map_err(|e| format!("Invalid lock script: {e:?}"))?,
+            None => {
+                let hash = parse_owner_script_hash(input).map_err(|e| e.to_string())?;
+                format!("0x{}", hex::encode(hash))
+            }
+        };
+        Ok(Self {
+            label: label.trim().to_owned(),
+            address,
+            script,
+            lock_hash,
+        })
+    }
+}
+
+/// Owner locks used over and over, labeled so they are recognizable wherever
+/// an owner hash is shown.
+#[derive(Default, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct AddressBook {
+    owners: Vec<Owner>,
+    #[serde(skip)]
+    new_label: String,
+    #[serde(skip)]
+    new_owner: String,
+    #[serde(skip)]
+    error: Option<String>,
+}
+
+impl AddressBook {
+    pub fn label(&self, lock_hash: &str) -> Option<&str> {
+        let lock_hash = lock_hash.trim().to_lowercase();
+        self.owners
+            .iter()
+            .find(|owner| strip_0x(&owner.lock_hash) == strip_0x(&lock_hash))
+            .map(|owner| owner.label.as_str())
+    }
+
+    /// `lock_hash` with its label in front, when it's in the book.
+    pub fn describe(&self, lock_hash: &str) -> String {
+        match self.label(lock_hash) {
+            Some(label) => format!("{label} ({lock_hash})"),
+            None => lock_hash.to_owned(),
+        }
+    }
+
+    /// An owner hash in a table: its label, if any, and the copyable hash.
+    pub fn owner_ui(&self, ui: &mut egui::Ui, lock_hash: &str) {
+        ui.horizontal(|ui| {
+            if let Some(label) = self.label(lock_hash) {
+                ui.label(egui::RichText::new(label).strong());
+            }
+            copyable(ui, lock_hash);
+        });
+    }
+
+    /// A menu of the saved owners with a known script, returns whether
+    /// `script` was set to one.
+    pub fn pick_script_ui(&self, ui: &mut egui::Ui, script: &mut Script) -> bool {
+        let mut picked = false;
+        let owners: Vec<_> = self.owners.iter().filter(|o| o.script.is_some()).collect();
+        ui.add_enabled_ui(!owners.is_empty(), |ui| {
+            ui.menu_button("Address Book", |ui| {
+                for owner in owners {
+                    if ui.button(&owner.label).clicked() {
+                        *script = owner.script.clone().expect("filtered on scripts");
+                        picked = true;
+                        ui.close_menu();
+                    }
+                }
+            })
+            .response
+            .on_disabled_hover_text(
+                "Add owners by address or lock script in the Address Book section",
+            );
+        });
+        picked
+    }
+
+    /// A menu of the saved owners, returns whether `lock_hash` was set to one.
+    pub fn pick_ui(&self, ui: &mut egui::Ui, lock_hash: &mut String) -> bool {
+        let mut picked = false;
+        ui.add_enabled_ui(!self.owners.is_empty(), |ui| {
+            ui.menu_button("Address Book", |ui| {
+                for owner in &self.owners {
+                    if ui
+                        .button(&owner.label)
+                        .on_hover_text(owner.address.as_deref().unwrap_or(&owner.lock_hash))
+                        .clicked()
+                    {
+                        *lock_hash = owner.lock_hash.clone();
+                        picked = true;
+                        ui.close_menu();
+                    }
+                }
+            })
+            .response
+            .on_disabled_hover_text("Add owners in the Address Book section");
+        });
+        picked
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Label");
+            ui.add(egui::TextEdit::singleline(&mut self.new_label).desired_width(120.0));
+            ui.label("Owner");
+            ui.add(
+                egui::TextEdit::singleline(&mut self.new_owner)
+                    .hint_text("address, lock script JSON or lock hash"),
+            );
+            if ui
+                .add_enabled(!self.new_label.trim().is_empty(), egui::Button::new("Add"))
+                .clicked()
+            {
+                match Owner::parse(&self.new_label, &self.new_owner) {
+                    Ok(owner) => {
+                        self.owners.retain(|o| o.lock_hash != owner.lock_hash);
+                        self.owners.push(owner);
+                        self.new_label.clear();
+                        self.new_owner.clear();
+                        self.error = None;
+                    }
+                    Err(e) => self.error = Some(e),
+                }
+            }
+        });
+        if let Some(e) = &self.error {
+            ui.label(egui::RichText::new(e).color(egui::Color32::RED));
+        }
+        if self.owners.is_empty() {
+            ui.label(egui::RichText::new("No owners saved yet").color(egui::Color32::PLACEHOLDER));
+            return;
+        }
+
+        let mut remove = None;
+        egui::Grid::new("address_book")
+            .striped(true)
+            .show(ui, |ui| {
+                for header in ["Label", "Address", "Script", "Lock Hash", ""] {
+                    ui.label(egui::RichText::new(header).strong());
+                }
+                ui.end_row();
+                for (i, owner) in self.owners.iter_mut().enumerate() {
+                    ui.add(egui::TextEdit::singleline(&mut owner.label).desired_width(120.0));
+                    match &owner.address {
+                        Some(address) => copyable(ui, address),
+                        None => {
+                            ui.label("-");
+                        }
+                    }
+                    match &owner.script {
+                        Some(script) => copyable(
+                            ui,
+                            &serde_json::to_string(script).expect("a script always serializes"),
+                        ),
+                        None => {
+                            ui.label("-");
+                        }
+                    }
+                    copyable(ui, &owner.lock_hash);
+                    if ui.button("Delete").clicked() {
+                        remove = Some(i);
+                    }
+                    ui.end_row();
+                }
+            });
+        if let Some(i) = remove {
+            self.owners.remove(i);
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use super::address_book::AddressBook;
+use crate::dex::{mode_description, DexArgs, DEX_ARGS_FIELDS, DEX_ARGS_LEN};
+
+/// How long bytes stay highlighted after they changed.
+const CHANGE_HIGHLIGHT_SECS: f64 = 2.0;
+
+const FIELD_COLORS: [egui::Color32; 4] = [
+    egui::Color32::from_rgb(0x8e, 0x44, 0xad),
+    egui::Color32::from_rgb(0x27, 0x60, 0xa0),
+    egui::Color32::from_rgb(0x1e, 0x84, 0x49),
+    egui::Color32::from_rgb(0xb9, 0x77, 0x0e),
+];
+
+/// Hex view of the 42 args bytes, colored by field.
+pub struct ByteMap {
+    last: Option<[u8; DEX_ARGS_LEN]>,
+    /// `egui::InputState::time` each byte last changed at.
+    changed_at: [Option<f64>; DEX_ARGS_LEN],
+    /// Field under the pointer last frame.
+    hovered: Option<usize>,
+}
+
+impl Default for ByteMap {
+    fn default() -> Self {
+        Self {
+            last: None,
+            changed_at: [None; DEX_ARGS_LEN],
+            hovered: None,
+        }
+    }
+}
+
+fn field_of(byte: usize) -> usize {
+    DEX_ARGS_FIELDS
+        .iter()
+        .position(|(_, range)| range.contains(&byte))
+        .expect("every byte belongs to a field")
+}
+
+/// Decoded value of the `DEX_ARGS_FIELDS[field]` of `args`.
+pub fn field_value(args: &DexArgs, field: usize, address_book: &AddressBook) -> String {
+    match field {
+        0 => format!("{} ({})", args.mode, mode_description(args.mode)),
+        1 => address_book.describe(&args.owner_script_hash_hex()),
+        2 => args.price_base.to_string(),
+        _ => args.price_pow.to_string(),
+    }
+}
+
+impl ByteMap {
+    pub fn ui(&mut self, ui: &mut egui::Ui, args: &DexArgs, address_book: &AddressBook) {
+        let bytes = args.to_bytes();
+        let now = ui.input(|i| i.time);
+        if let Some(last) = self.last {
+            for (i, changed_at) in self.changed_at.iter_mut().enumerate() {
+                if last[i] != bytes[i] {
+                    *changed_at = Some(now);
+                }
+            }
+        }
+        self.last = Some(bytes);
+
+        let mut hovered = None;
+        let mut fading = false;
+        ui.horizontal_wrapped(|ui| {
+            ui.spacing_mut().item_spacing.x = 2.0;
+            for (i, byte) in bytes.iter().enumerate() {
+                let field = field_of(i);
+                let mut color = FIELD_COLORS[field];
+                if self.hovered != Some(field) {
+                    color = color.gamma_multiply(0.6);
+                }
+                let mut text = egui::RichText::new(format!("{byte:02x}"))
+                    .monospace()
+                    .color(egui::Color32::WHITE)
+                    .background_color(color);
+                if let Some(age) = self.changed_at[i].map(|t| now - t) {
+                    if age < CHANGE_HIGHLIGHT_SECS {
+                        text = text.color(egui::Color32::YELLOW).underline();
+                        fading = true;
+                    }
+                }
+                let response = ui.add(egui::Label::new(text).sense(egui::Sense::hover()));
+                if response.hovered() {
+                    hovered = Some(field);
+                }
+                response.on_hover_text(format!(
+                    "byte {i}, {}[{}]",
+                    DEX_ARGS_FIELDS[field].0,
+                    i - DEX_ARGS_FIELDS[field].1.start
+                ));
+            }
+        });
+        if fading {
+            ui.ctx()
+                .request_repaint_after(std::time::Duration::from_millis(100));
+        }
+        if hovered != self.hovered {
+            ui.ctx().request_repaint();
+        }
+        self.hovered = hovered;
+
+        egui::Grid::new("byte_map_fields").show(ui, |ui| {
+            for (field, (name, range)) in DEX_ARGS_FIELDS.iter().enumerate() {
+                let mut label = egui::RichText::new(*name).background_color(FIELD_COLORS[field]);
+                let mut value =
+                    egui::RichText::new(field_value(args, field, address_book)).monospace();
+                if self.hovered == Some(field) {
+                    label = label.strong();
+                    value = value.strong().color(egui::Color32::LIGHT_GREEN);
+                }
+                ui.label(label);
+                ui.label(format!("bytes {}..{}", range.start, range.end));
+                ui.label(value);
+                ui.end_row();
+            }
+        });
+    }
+}
@@ -0,0 +1,155 @@
+use super::address_book::AddressBook;
+use super::byte_map::field_value;
+use crate::dex::{DexArgs, DEX_ARGS_FIELDS};
+use crate::diagnose::normalize_args;
+use crate::rpc::format_ckb;
+
+const DIFF_COLOR: egui::Color32 = egui::Color32::from_rgb(0xc0, 0x39, 0x2b);
+
+/// Two args decoded side by side, e.g. a listed order against what a seller claims.
+#[derive(Default, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct Compare {
+    left: String,
+    right: String,
+    /// Raw UDT amount the prices are computed for, only used in UDT mode.
+    amount: String,
+}
+
+fn price_label(price: Option<u128>) -> String {
+    match price {
+        Some(shannons) => format!("{} CKB", format_ckb(shannons)),
+        None => "overflows".to_owned(),
+    }
+}
+
+/// The args bytes, the ones in `differs` highlighted.
+fn hex_row(ui: &mut egui::Ui, bytes: &[u8], differs: &[bool]) {
+    ui.horizontal_wrapped(|ui| {
+        ui.spacing_mut().item_spacing.x = 2.0;
+        for (i, byte) in bytes.iter().enumerate() {
+            let mut text = egui::RichText::new(format!("{byte:02x}")).monospace();
+            if differs[i] {
+                text = text
+                    .color(egui::Color32::WHITE)
+                    .background_color(DIFF_COLOR);
+            }
+            ui.label(text);
+        }
+    });
+}
+
+impl Compare {
+    /// Put `args` on the left, to check them against pasted ones.
+    pub fn set_left(&mut self, args: &str) {
+        self.left = args.to_owned();
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui, address_book: &AddressBook) {
+        ui.columns(2, |columns| {
+            columns[0].label("Left args");
+            columns[0].add(egui::TextEdit::multiline(&mut self.left).desired_rows(2));
+            columns[1].label("Right args");
+            columns[1].add(egui::TextEdit::multiline(&mut self.right).desired_rows(2));
+        });
+        ui.horizontal(|ui| {
+            ui.label("UDT amount");
+            ui.text_edit_singleline(&mut self.amount)
+                .on_hover_text("Raw amount from the cell data, only used in UDT mode");
+            if ui.button("Swap").clicked() {
+                std::mem::swap(&mut self.left, &mut self.right);
+            }
+        });
+
+        // pasted args often come quoted or wrapped
+        let decode = |args: &str| DexArgs::decode(&normalize_args(args));
+        let (left, right) = match (decode(&self.left), decode(&self.right)) {
+            (Ok(left), Ok(right)) => (left, right),
+            (left, right) => {
+                for (side, result) in [("Left", left), ("Right", right)] {
+                    if let Err(e) = result {
+                        ui.colored_label(egui::Color32::RED, format!("{side} args: {e}"));
+                    }
+                }
+                return;
+            }
+        };
+
+        let left_bytes = left.to_bytes();
+        let right_bytes = right.to_bytes();
+        let differs: Vec<bool> = left_bytes
+            .iter()
+            .zip(&right_bytes)
+            .map(|(l, r)| l != r)
+            .collect();
+        let differing = differs.iter().filter(|&&d| d).count();
+        if differing == 0 {
+            ui.colored_label(egui::Color32::GREEN, "The args are identical");
+        } else {
+            ui.colored_label(DIFF_COLOR, format!("{differing} bytes differ"));
+        }
+
+        egui::Grid::new("compare_fields")
+            .striped(true)
+            .show(ui, |ui| {
+                ui.strong("Field");
+                ui.strong("Left");
+                ui.strong("Right");
+                ui.end_row();
+                for (field, (name, range)) in DEX_ARGS_FIELDS.iter().enumerate() {
+                    let changed = differs[range.clone()].contains(&true);
+                    let text = |args: &DexArgs| {
+                        let text =
+                            egui::RichText::new(field_value(args, field, address_book)).monospace();
+                        if changed {
+                            text.color(DIFF_COLOR).strong()
+                        } else {
+                            text
+                        }
+                    };
+                    ui.label(*name);
+                    ui.label(text(&left));
+                    ui.label(text(&right));
+                    ui.end_row();
+                }
+            });
+
+        let amount = match self.amount.trim() {
+            "" => Some(0),
+            amount => amount.parse::<u128>().ok(),
+        };
+        let Some(amount) = amount else {
+            ui.colored_label(egui::Color32::RED, "The UDT amount must be a whole number");
+            return;
+        };
+        let left_price = left.total_price(amount);
+        let right_price = right.total_price(amount);
+        ui.horizontal(|ui| {
+            ui.label(format!(
+                "Total price: left {}, right {}",
+                price_label(left_price),
+                price_label(right_price)
+            ));
+            if let (Some(l), Some(r)) = (left_price, right_price) {
+                let difference = match r.cmp(&l) {
+                    std::cmp::Ordering::Equal => "same price".to_owned(),
+                    std::cmp::Ordering::Greater => {
+                        format!("right is {} CKB more", format_ckb(r - l))
+                    }
+                    std::cmp::Ordering::Less => format!("right is {} CKB less", format_ckb(l - r)),
+                };
+                let color = if l == r {
+                    egui::Color32::GREEN
+                } else {
+                    DIFF_COLOR
+                };
+                ui.colored_label(color, difference);
+            }
+        });
+
+        ui.label("Left bytes");
+        hex_row(ui, &left_bytes, &differs);
+        ui.label("Right bytes");
+        hex_row(ui, &right_bytes, &differs);
+    }
+}
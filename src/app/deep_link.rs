@@ -0,0 +1,163 @@
+use super::pages::Page;
+use crate::dex::MAX_MODE;
+
+/// Scheme of links opening the native app, e.g. `dex-helper://#decode=0x...`.
+pub const SCHEME: &str = "dex-helper://";
+
+/// An order (or just a page) to open, from a link.
+///
+/// The parameters are `key=value` pairs joined by `&`, in the URL fragment or
+/// query: `#decode=0x...` or `#mode=0&owner=0x...&base=5&pow=8`. A bare page
+/// name like `#inspect` opens that page.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DeepLink {
+    pub page: Option<Page>,
+    /// Args (or anything the args box accepts) to decode.
+    pub args: Option<String>,
+    pub mode: Option<u16>,
+    pub owner: Option<String>,
+    pub amount: Option<u64>,
+    pub price_base: Option<u32>,
+    pub price_pow: Option<u32>,
+}
+
+fn number<T: std::str::FromStr>(key: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("{key} must be a number, got {value:?}"))
+}
+
+impl DeepLink {
+    /// Parse the parameters of a web URL, a `dex-helper://` link or a bare
+    /// fragment or query.
+    pub fn parse(link: &str) -> Result<Self, String> {
+        let link = link.trim();
+        let params = match link.find('#').or_else(|| link.find('?')) {
+            Some(i) => &link[i + 1..],
+            None => link.strip_prefix(SCHEME).unwrap_or(link),
+        };
+        let mut parsed = Self::default();
+        for param in params.split('&').filter(|p| !p.is_empty()) {
+            let Some((key, value)) = param.split_once('=') else {
+                parsed.page = Some(
+                    Page::from_fragment(param).ok_or_else(|| format!("Unknown page {param:?}"))?,
+                );
+                continue;
+            };
+            let value = value.trim();
+            match key {
+                "decode" | "args" => parsed.args = Some(value.to_owned()),
+                "mode" => {
+                    let mode = number(key, value)?;
+                    if mode > MAX_MODE {
+                        return Err(format!("mode must be 0, 1 or 2, got {mode}"));
+                    }
+                    parsed.mode = Some(mode);
+                }
+                "owner" => parsed.owner = Some(value.to_owned()),
+                "amount" => parsed.amount = Some(number(key, value)?),
+                "base" => parsed.price_base = Some(number(key, value)?),
+                "pow" => parsed.price_pow = Some(number(key, value)?),
+                "page" => {
+                    parsed.page = Some(
+                        Page::from_fragment(value)
+                            .ok_or_else(|| format!("Unknown page {value:?}"))?,
+                    )
+                }
+                _ => return Err(format!("Unknown link parameter {key:?}")),
+            }
+        }
+        Ok(parsed)
+    }
+
+    /// Whether the link carries an order, not only a page.
+    pub fn has_order(&self) -> bool {
+        self.args.is_some()
+            || self.mode.is_some()
+            || self.owner.is_some()
+            || self.amount.is_some()
+            || self.price_base.is_some()
+            || self.price_pow.is_some()
+    }
+}
+
+/// The URL of the page without its fragment, what links are built on.
+pub fn page_url(url: &str) -> &str {
+    url.split('#').next().unwrap_or_default()
+}
+
+/// Link to `args`, opening the decoder. `base` is the page URL on the web,
+/// [`SCHEME`] natively, any fragment it has is replaced.
+pub fn share_link(base: &str, args: &str) -> String {
+    format!("{}#decode={args}", page_url(base))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ARGS: &str =
+        "0x010029e560749fc5c785e3c1cfc3e14c0e608ec87120f0c066facf023ee8bd569d846400000008000000";
+    const OWNER: &str = "0x29e560749fc5c785e3c1cfc3e14c0e608ec87120f0c066facf023ee8bd569d84";
+
+    #[test]
+    fn share_links_round_trip() {
+        for base in ["https://example.com/dex/index.html", SCHEME] {
+            let link = share_link(base, ARGS);
+            let parsed = DeepLink::parse(&link).unwrap();
+            assert_eq!(parsed.args.as_deref(), Some(ARGS));
+            assert!(parsed.has_order());
+            assert_eq!(parsed.page, None);
+        }
+    }
+
+    #[test]
+    fn share_links_drop_the_open_fragment() {
+        // the app was opened on a page or another order
+        for base in [
+            "https://example.com/index.html#inspect",
+            "https://example.com/index.html#decode=0x00",
+        ] {
+            let link = share_link(base, ARGS);
+            assert_eq!(
+                link,
+                format!("https://example.com/index.html#decode={ARGS}")
+            );
+            assert_eq!(DeepLink::parse(&link).unwrap().args.as_deref(), Some(ARGS));
+        }
+        assert_eq!(page_url("https://example.com/"), "https://example.com/");
+    }
+
+    #[test]
+    fn encoder_links() {
+        let link = format!("https://example.com/?mode=0&owner={OWNER}&amount=5&base=100&pow=8");
+        assert_eq!(
+            DeepLink::parse(&link).unwrap(),
+            DeepLink {
+                page: None,
+                args: None,
+                mode: Some(0),
+                owner: Some(OWNER.to_owned()),
+                amount: Some(5),
+                price_base: Some(100),
+                price_pow: Some(8),
+            }
+        );
+        let page = DeepLink::parse("#inspect").unwrap();
+        assert_eq!(page.page, Some(Page::Inspect));
+        assert!(!page.has_order());
+    }
+
+    #[test]
+    fn invalid_links() {
+        assert_eq!(
+            DeepLink::parse("#mode=3&base=1").unwrap_err(),
+            "mode must be 0, 1 or 2, got 3"
+        );
+        assert!(DeepLink::parse("#mode=2").is_ok());
+        assert!(DeepLink::parse("#mode=-1").is_err());
+        assert!(DeepLink::parse("#base=lots").is_err());
+        assert!(DeepLink::parse("#nowhere").is_err());
+        assert!(DeepLink::parse("#colour=red").is_err());
+    }
+}
@@ -0,0 +1,155 @@
+/// One order being worked on, what the encoder fields hold while it's the active tab.
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct Draft {
+    pub name: String,
+    pub mode: u16,
+    pub owner_script_hash: String,
+    pub amount: u64,
+    pub price_base: u32,
+    pub price_pow: u32,
+    pub encoded_string: String,
+}
+
+impl Default for Draft {
+    fn default() -> Self {
+        Self {
+            name: "Order 1".to_owned(),
+            mode: 0,
+            owner_script_hash: "".to_owned(),
+            amount: 1,
+            price_base: 1,
+            price_pow: 0,
+            encoded_string: "0x".to_owned(),
+        }
+    }
+}
+
+/// Named order drafts shown as tabs.
+#[derive(Default, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct Drafts {
+    drafts: Vec<Draft>,
+    active: usize,
+    /// Tab whose name is being edited.
+    #[serde(skip)]
+    renaming: Option<usize>,
+}
+
+impl Drafts {
+    /// Nothing saved yet, e.g. state from before drafts existed.
+    pub fn is_empty(&self) -> bool {
+        self.drafts.is_empty()
+    }
+
+    pub fn active(&self) -> &Draft {
+        &self.drafts[self.active]
+    }
+
+    /// Keep the active draft up to date with the encoder fields.
+    pub fn store(&mut self, draft: Draft) {
+        self.fix();
+        let name = std::mem::take(&mut self.drafts[self.active].name);
+        self.drafts[self.active] = Draft { name, ..draft };
+    }
+
+    /// Add an empty draft and make it the active one.
+    pub fn push(&mut self, name: &str) {
+        self.drafts.push(Draft {
+            name: name.to_owned(),
+            ..Default::default()
+        });
+        self.active = self.drafts.len() - 1;
+    }
+
+    /// A name not used by any draft yet, `Order N`.
+    fn new_name(&self) -> String {
+        (1..)
+            .map(|n| format!("Order {n}"))
+            .find(|name| self.drafts.iter().all(|d| &d.name != name))
+            .expect("names are unbounded")
+    }
+
+    /// Make sure `active` points at a draft, after loading state saved by an older version.
+    fn fix(&mut self) {
+        if self.drafts.is_empty() {
+            self.drafts.push(Draft::default());
+        }
+        self.active = self.active.min(self.drafts.len() - 1);
+    }
+
+    /// The tab bar, returns whether another draft became active and has to be
+    /// loaded into the encoder fields.
+    pub fn ui(&mut self, ui: &mut egui::Ui) -> bool {
+        self.fix();
+        let active = self.active;
+        ui.horizontal_wrapped(|ui| {
+            for i in 0..self.drafts.len() {
+                if self.renaming == Some(i) {
+                    let response = ui.text_edit_singleline(&mut self.drafts[i].name);
+                    if response.lost_focus() {
+                        self.renaming = None;
+                    } else {
+                        response.request_focus();
+                    }
+                    continue;
+                }
+                let response = ui
+                    .selectable_label(i == self.active, &self.drafts[i].name)
+                    .on_hover_text("Double click to rename");
+                if response.clicked() {
+                    self.active = i;
+                }
+                if response.double_clicked() {
+                    self.renaming = Some(i);
+                }
+            }
+            ui.separator();
+            if ui.button("New").clicked() {
+                let name = self.new_name();
+                self.drafts.push(Draft {
+                    name,
+                    owner_script_hash: self.active().owner_script_hash.clone(),
+                    ..Default::default()
+                });
+                self.active = self.drafts.len() - 1;
+            }
+            if ui.button("Duplicate").clicked() {
+                let mut draft = self.active().clone();
+                draft.name = format!("{} copy", draft.name);
+                self.drafts.insert(self.active + 1, draft);
+                self.active += 1;
+            }
+            if ui
+                .add_enabled(self.active > 0, egui::Button::new("Move Left"))
+                .clicked()
+            {
+                self.drafts.swap(self.active, self.active - 1);
+                self.active -= 1;
+            }
+            if ui
+                .add_enabled(
+                    self.active + 1 < self.drafts.len(),
+                    egui::Button::new("Move Right"),
+                )
+                .clicked()
+            {
+                self.drafts.swap(self.active, self.active + 1);
+                self.active += 1;
+            }
+            if ui
+                .add_enabled(self.drafts.len() > 1, egui::Button::new("Delete"))
+                .clicked()
+            {
+                self.drafts.remove(self.active);
+                self.active = self.active.min(self.drafts.len() - 1);
+                self.renaming = None;
+                // the active tab changed even if the index didn't
+                return true;
+            }
+            false
+        })
+        .inner
+            || self.active != active
+    }
+}
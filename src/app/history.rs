@@ -0,0 +1,176 @@
+use super::drafts::Draft;
+
+/// Changes to the same fields closer together than this are one step, so a
+/// slider drag or typing a hash undoes at once.
+const COALESCE_SECS: f64 = 1.0;
+
+/// Most steps kept.
+const MAX_STEPS: usize = 100;
+
+struct Step {
+    /// The fields before (in `undo`) or after (in `redo`) the change.
+    draft: Draft,
+    description: String,
+}
+
+/// Undo/redo over the order fields of the active draft.
+#[derive(Default)]
+pub struct History {
+    undo: Vec<Step>,
+    redo: Vec<Step>,
+    current: Option<Draft>,
+    /// Fields the last recorded change touched, and when.
+    last_change: Option<(Vec<&'static str>, f64)>,
+}
+
+fn changed_fields(before: &Draft, after: &Draft) -> Vec<&'static str> {
+    let mut fields = vec![];
+    if before.mode != after.mode {
+        fields.push("mode");
+    }
+    if before.owner_script_hash != after.owner_script_hash {
+        fields.push("owner");
+    }
+    if before.amount != after.amount {
+        fields.push("amount");
+    }
+    if before.price_base != after.price_base {
+        fields.push("price_base");
+    }
+    if before.price_pow != after.price_pow {
+        fields.push("price_pow");
+    }
+    if before.encoded_string != after.encoded_string {
+        fields.push("args");
+    }
+    fields
+}
+
+fn describe(before: &Draft, after: &Draft, fields: &[&str]) -> String {
+    let parts: Vec<String> = fields
+        .iter()
+        // the args follow the other fields, only worth a mention when edited directly
+        .filter(|&&field| field != "args" || fields.len() == 1)
+        .map(|&field| match field {
+            "mode" => format!("mode {} → {}", before.mode, after.mode),
+            "amount" => format!("amount {} → {}", before.amount, after.amount),
+            "price_base" => format!("price_base {} → {}", before.price_base, after.price_base),
+            "price_pow" => format!("price_pow {} → {}", before.price_pow, after.price_pow),
+            field => format!("{field} edited"),
+        })
+        .collect();
+    parts.join(", ")
+}
+
+impl History {
+    /// Forget everything, e.g. when another draft becomes active.
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Called every frame with the fields as they are now.
+    pub fn record(&mut self, draft: Draft, time: f64) {
+        let Some(current) = self.current.replace(draft.clone()) else {
+            return;
+        };
+        let fields = changed_fields(&current, &draft);
+        if fields.is_empty() {
+            return;
+        }
+        let coalesce = matches!(
+            &self.last_change,
+            Some((last_fields, last_time)) if *last_fields == fields && time - last_time < COALESCE_SECS
+        );
+        match self.undo.last_mut() {
+            Some(step) if coalesce => {
+                let fields = changed_fields(&step.draft, &draft);
+                if fields.is_empty() {
+                    // dragged back to where it started
+                    self.undo.pop();
+                } else {
+                    step.description = describe(&step.draft, &draft, &fields);
+                }
+            }
+            _ => {
+                self.undo.push(Step {
+                    description: describe(&current, &draft, &fields),
+                    draft: current,
+                });
+                if self.undo.len() > MAX_STEPS {
+                    self.undo.remove(0);
+                }
+            }
+        }
+        self.redo.clear();
+        self.last_change = Some((fields, time));
+    }
+
+    /// The fields to restore, if there is anything to undo.
+    pub fn undo(&mut self) -> Option<Draft> {
+        let step = self.undo.pop()?;
+        self.move_to(step, true)
+    }
+
+    pub fn redo(&mut self) -> Option<Draft> {
+        let step = self.redo.pop()?;
+        self.move_to(step, false)
+    }
+
+    fn move_to(&mut self, step: Step, undo: bool) -> Option<Draft> {
+        let current = self.current.replace(step.draft.clone())?;
+        let back = Step {
+            draft: current,
+            description: step.description,
+        };
+        if undo {
+            self.redo.push(back);
+        } else {
+            self.undo.push(back);
+        }
+        self.last_change = None;
+        Some(step.draft)
+    }
+
+    /// Undo/Redo buttons and the list of steps, returns the fields to restore
+    /// when one was clicked.
+    pub fn ui(&mut self, ui: &mut egui::Ui) -> Option<Draft> {
+        let mut restore = None;
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(!self.undo.is_empty(), egui::Button::new("Undo"))
+                .on_hover_text("Ctrl+Z")
+                .clicked()
+            {
+                restore = self.undo();
+            }
+            if ui
+                .add_enabled(!self.redo.is_empty(), egui::Button::new("Redo"))
+                .on_hover_text("Ctrl+Shift+Z")
+                .clicked()
+            {
+                restore = self.redo();
+            }
+        });
+        if self.undo.is_empty() && self.redo.is_empty() {
+            ui.label(egui::RichText::new("No edits yet").color(egui::Color32::PLACEHOLDER));
+        }
+        egui::ScrollArea::vertical()
+            .id_source("edit_history")
+            .max_height(120.0)
+            .show(ui, |ui| {
+                // newest first: the undone steps greyed out above the current state
+                for step in &self.redo {
+                    ui.label(
+                        egui::RichText::new(&step.description).color(egui::Color32::PLACEHOLDER),
+                    );
+                }
+                if !self.redo.is_empty() || !self.undo.is_empty() {
+                    ui.label(egui::RichText::new("— current —").strong());
+                }
+                for step in self.undo.iter().rev() {
+                    ui.label(&step.description);
+                }
+            });
+        restore
+    }
+}
@@ -0,0 +1,349 @@
+use std::collections::BTreeMap;
+
+use poll_promise::Promise;
+
+use super::address_book::AddressBook;
+
+use crate::dex::{udt_amount, DexArgs};
+use crate::rpc::{format_ckb, DexCell, IndexerCell, Pagination, RpcClient, RpcError, SearchKey};
+
+const PAGE_SIZE: u32 = 100;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum SortBy {
+    #[default]
+    UnitPrice,
+    Amount,
+    Owner,
+    OutPoint,
+}
+
+/// A live cell locked by the dex lock whose args decode fine.
+#[derive(Clone, Debug)]
+pub struct Offer {
+    pub cell: DexCell,
+    pub args: DexArgs,
+    /// UDT amount read from cell data, only meaningful in mode 0.
+    pub amount: Option<u128>,
+}
+
+impl Offer {
+    fn new(cell: DexCell, args: DexArgs) -> Self {
+        Self {
+            amount: udt_amount(&cell.data_bytes()),
+            cell,
+            args,
+        }
+    }
+
+    /// `None` on overflow, or for a mode 0 cell without an amount to price.
+    pub fn total_price(&self) -> Option<u128> {
+        match (self.args.mode, self.amount) {
+            (0, None) => None,
+            (_, amount) => self.args.total_price(amount.unwrap_or_default()),
+        }
+    }
+}
+
+/// Live dex cells matching a list of indexer search keys, loaded page by page.
+#[derive(Default)]
+pub struct OrderBook {
+    search_keys: Vec<SearchKey>,
+    /// Index into `search_keys` of the key being paged through.
+    key_index: usize,
+    cursor: Option<String>,
+    fetch: Option<Promise<Result<Pagination<IndexerCell>, RpcError>>>,
+    offers: Vec<Offer>,
+    /// Cells locked by the dex lock whose args don't decode.
+    invalid: Vec<DexCell>,
+    error: Option<RpcError>,
+    sort_by: SortBy,
+    descending: bool,
+}
+
+impl OrderBook {
+    /// Drop everything loaded so far and start over with `search_keys`.
+    pub fn reset(&mut self, search_keys: Vec<SearchKey>) {
+        *self = Self {
+            search_keys,
+            sort_by: self.sort_by,
+            descending: self.descending,
+            ..Default::default()
+        };
+    }
+
+    pub fn is_fetching(&self) -> bool {
+        self.fetch.is_some()
+    }
+
+    pub fn is_exhausted(&self) -> bool {
+        self.key_index >= self.search_keys.len()
+    }
+
+    /// Request the next page of the current search key.
+    pub fn fetch_next_page(&mut self, ctx: &egui::Context, rpc_url: &str) {
+        let Some(search_key) = self.search_keys.get(self.key_index) else {
+            return;
+        };
+        let (sender, promise) = Promise::new();
+        let ctx = ctx.clone();
+        RpcClient::new(rpc_url.trim()).get_cells(
+            search_key,
+            PAGE_SIZE,
+            self.cursor.as_deref(),
+            move |result| {
+                sender.send(result);
+                ctx.request_repaint();
+            },
+        );
+        self.error = None;
+        self.fetch = Some(promise);
+    }
+
+    fn poll(&mut self) {
+        let Some(promise) = self.fetch.take() else {
+            return;
+        };
+        match promise.try_take() {
+            Err(promise) => self.fetch = Some(promise),
+            Ok(Err(e)) => self.error = Some(e),
+            Ok(Ok(page)) => {
+                if page.objects.len() < PAGE_SIZE as usize {
+                    self.key_index += 1;
+                    self.cursor = None;
+                } else {
+                    self.cursor = Some(page.last_cursor);
+                }
+                for cell in page.objects {
+                    let cell = DexCell::from_indexer(cell);
+                    match cell.args.clone() {
+                        Ok(args) => self.offers.push(Offer::new(cell, args)),
+                        Err(_) => self.invalid.push(cell),
+                    }
+                }
+                self.sort();
+            }
+        }
+    }
+
+    fn sort(&mut self) {
+        let sort_by = self.sort_by;
+        let descending = self.descending;
+        self.offers.sort_by(|a, b| {
+            let ordering = match sort_by {
+                SortBy::UnitPrice => a.args.unit_price().cmp(&b.args.unit_price()),
+                SortBy::Amount => a.amount.cmp(&b.amount),
+                SortBy::Owner => a.args.owner_script_hash.cmp(&b.args.owner_script_hash),
+                SortBy::OutPoint => std::cmp::Ordering::Equal,
+            }
+            .then_with(|| {
+                a.cell
+                    .out_point
+                    .to_string()
+                    .cmp(&b.cell.out_point.to_string())
+            });
+            if descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        });
+    }
+
+    /// Show the loaded offers, returns the offer whose `Load` button was clicked.
+    ///
+    /// `actions` adds extra buttons at the end of each row.
+    pub fn ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        rpc_url: &str,
+        address_book: &AddressBook,
+        mut actions: impl FnMut(&mut egui::Ui, &Offer),
+    ) -> Option<Offer> {
+        self.poll();
+
+        ui.horizontal(|ui| {
+            let more = !self.offers.is_empty() || !self.invalid.is_empty();
+            let text = if more { "Load More" } else { "Load" };
+            if ui
+                .add_enabled(
+                    !self.is_fetching() && !self.is_exhausted(),
+                    egui::Button::new(text),
+                )
+                .clicked()
+            {
+                self.fetch_next_page(ui.ctx(), rpc_url);
+            }
+            if self.is_fetching() {
+                ui.spinner();
+            }
+            ui.label(format!(
+                "{} offers, {} undecodable cells{}",
+                self.offers.len(),
+                self.invalid.len(),
+                if self.is_exhausted() {
+                    ", all loaded"
+                } else {
+                    ""
+                }
+            ));
+        });
+        if let Some(e) = &self.error {
+            ui.label(egui::RichText::new(e.to_string()).color(egui::Color32::RED));
+        }
+
+        let mut groups: BTreeMap<String, Vec<&Offer>> = BTreeMap::new();
+        for offer in &self.offers {
+            groups
+                .entry(type_script_label(&offer.cell))
+                .or_default()
+                .push(offer);
+        }
+
+        let mut picked = None;
+        let mut sort_clicked = None;
+        for (label, offers) in groups {
+            egui::CollapsingHeader::new(format!("{label} ({} offers)", offers.len()))
+                .id_source(&label)
+                .default_open(true)
+                .show(ui, |ui| {
+                    egui::Grid::new(&label)
+                        .striped(true)
+                        .num_columns(7)
+                        .show(ui, |ui| {
+                            for (sort_by, text) in [
+                                (SortBy::UnitPrice, "Price per unit"),
+                                (SortBy::Amount, "Amount"),
+                            ] {
+                                if sort_header(ui, self.sort_by, self.descending, sort_by, text) {
+                                    sort_clicked = Some(sort_by);
+                                }
+                            }
+                            ui.label("Total");
+                            ui.label("Mode");
+                            for (sort_by, text) in
+                                [(SortBy::Owner, "Owner"), (SortBy::OutPoint, "OutPoint")]
+                            {
+                                if sort_header(ui, self.sort_by, self.descending, sort_by, text) {
+                                    sort_clicked = Some(sort_by);
+                                }
+                            }
+                            ui.label("");
+                            ui.end_row();
+
+                            for offer in offers {
+                                offer_row(ui, offer, address_book);
+                                ui.horizontal(|ui| {
+                                    if ui
+                                        .button("Load")
+                                        .on_hover_text("Load this offer into the encoder")
+                                        .clicked()
+                                    {
+                                        picked = Some(offer.clone());
+                                    }
+                                    actions(ui, offer);
+                                });
+                                ui.end_row();
+                            }
+                        });
+                });
+        }
+        if let Some(sort_by) = sort_clicked {
+            if self.sort_by == sort_by {
+                self.descending = !self.descending;
+            } else {
+                self.sort_by = sort_by;
+                self.descending = false;
+            }
+            self.sort();
+        }
+
+        if !self.invalid.is_empty() {
+            egui::CollapsingHeader::new(format!("Undecodable cells ({})", self.invalid.len()))
+                .default_open(false)
+                .show(ui, |ui| {
+                    for cell in &self.invalid {
+                        ui.label(format!(
+                            "{} args: {} ({:?})",
+                            cell.out_point,
+                            cell.output.lock.args,
+                            cell.args.as_ref().err()
+                        ));
+                    }
+                });
+        }
+        picked
+    }
+}
+
+fn sort_header(
+    ui: &mut egui::Ui,
+    current: SortBy,
+    descending: bool,
+    sort_by: SortBy,
+    text: &str,
+) -> bool {
+    let selected = current == sort_by;
+    let arrow = match (selected, descending) {
+        (false, _) => "",
+        (true, false) => " ⏶",
+        (true, true) => " ⏷",
+    };
+    ui.selectable_label(selected, format!("{text}{arrow}"))
+        .on_hover_text("Click to sort")
+        .clicked()
+}
+
+fn offer_row(ui: &mut egui::Ui, offer: &Offer, address_book: &AddressBook) {
+    let unit_price = offer
+        .args
+        .unit_price()
+        .map(|p| format!("{} CKB", format_ckb(p)))
+        .unwrap_or_else(|| "overflow".to_owned());
+    ui.label(unit_price);
+    match (offer.args.mode, offer.amount) {
+        (0, Some(amount)) => ui.label(amount.to_string()),
+        (0, None) => ui.label(egui::RichText::new("no amount").color(egui::Color32::RED)),
+        _ => ui.label("1 cell"),
+    };
+    let total = match (offer.args.mode, offer.total_price()) {
+        (0, None) if offer.amount.is_none() => "unknown".to_owned(),
+        (_, Some(p)) => format!("{} CKB", format_ckb(p)),
+        (_, None) => "overflow".to_owned(),
+    };
+    ui.label(total);
+    ui.label(offer.args.mode.to_string());
+    address_book.owner_ui(ui, &offer.args.owner_script_hash_hex());
+    copyable(ui, &offer.cell.out_point.to_string());
+}
+
+/// Groups cells holding the same asset.
+pub(super) fn type_script_label(cell: &DexCell) -> String {
+    match &cell.output.type_ {
+        Some(type_) => format!(
+            "Type: {} ({}) args: {}",
+            type_.code_hash, type_.hash_type, type_.args
+        ),
+        None => "Type: None (plain CKB)".to_owned(),
+    }
+}
+
+/// A shortened label that copies the full text when clicked.
+pub(super) fn copyable(ui: &mut egui::Ui, text: &str) {
+    // count chars, not bytes, names and labels may be any text
+    let chars = text.chars().count();
+    let short = if chars > 20 {
+        let head: String = text.chars().take(10).collect();
+        let tail: String = text.chars().skip(chars - 8).collect();
+        format!("{head}…{tail}")
+    } else {
+        text.to_owned()
+    };
+    if ui
+        .add(egui::Label::new(egui::RichText::new(short).monospace()).sense(egui::Sense::click()))
+        .on_hover_text(format!("{text}\nClick to copy"))
+        .clicked()
+    {
+        ui.output_mut(|o| o.copied_text = text.to_owned());
+    }
+}
@@ -0,0 +1,75 @@
+/// Pages listed in the side panel, one shown at a time.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, Hash, serde::Deserialize, serde::Serialize,
+)]
+pub enum Page {
+    #[default]
+    Encode,
+    Decode,
+    Contract,
+    BuildTx,
+    Inspect,
+    OrderBook,
+}
+
+impl Page {
+    pub const ALL: [Self; 6] = [
+        Self::Encode,
+        Self::Decode,
+        Self::Contract,
+        Self::BuildTx,
+        Self::Inspect,
+        Self::OrderBook,
+    ];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Self::Encode => "Encode",
+            Self::Decode => "Decode",
+            Self::Contract => "Contract",
+            Self::BuildTx => "Build Tx",
+            Self::Inspect => "Inspect",
+            Self::OrderBook => "Order Book",
+        }
+    }
+
+    /// URL fragment of the page on the web, without the `#`.
+    #[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+    pub fn fragment(self) -> &'static str {
+        match self {
+            Self::Encode => "encode",
+            Self::Decode => "decode",
+            Self::Contract => "contract",
+            Self::BuildTx => "build-tx",
+            Self::Inspect => "inspect",
+            Self::OrderBook => "order-book",
+        }
+    }
+
+    pub fn from_fragment(fragment: &str) -> Option<Self> {
+        let fragment = fragment.trim_start_matches('#');
+        Self::ALL
+            .into_iter()
+            .find(|page| page.fragment() == fragment)
+    }
+
+    /// The page list, returns whether another page was picked.
+    pub fn nav_ui(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut changed = false;
+        for page in Self::ALL {
+            changed |= ui.selectable_value(self, page, page.title()).changed();
+        }
+        changed
+    }
+}
+
+/// Point the browser's URL at `page`, adding a history entry so back and
+/// forward move between pages.
+#[cfg(target_arch = "wasm32")]
+pub fn set_fragment(page: Page) {
+    if let Some(window) = web_sys::window() {
+        if let Err(e) = window.location().set_hash(page.fragment()) {
+            log::warn!("Can't set the URL fragment: {e:?}");
+        }
+    }
+}
@@ -0,0 +1,240 @@
+use qrcodegen::QrCode;
+
+use super::order_book::copyable;
+use crate::address::Network;
+use crate::dex::DexArgs;
+use crate::qr::{self, QrError};
+use crate::tx::dex_lock_script;
+
+/// How long each frame of a chunked code stays on screen.
+const FRAME_SECS: f64 = 0.6;
+
+/// Largest side of the code on screen, in points.
+const MAX_SIDE: f32 = 320.0;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum QrSource {
+    #[default]
+    Args,
+    Address,
+    Transaction,
+}
+
+impl QrSource {
+    fn file_stem(self) -> &'static str {
+        match self {
+            Self::Args => "dex-args",
+            Self::Address => "dex-lock-address",
+            Self::Transaction => "unsigned-tx",
+        }
+    }
+}
+
+/// A QR code of the args, the dex lock address or the unsigned transaction,
+/// cycling through frames when the text doesn't fit in one.
+#[derive(Default)]
+pub struct QrView {
+    source: QrSource,
+    testnet: bool,
+    /// Codes rendered for a text, kept until the text changes.
+    cache: Option<(String, Result<Vec<QrCode>, QrError>)>,
+    frame: usize,
+    /// `egui::InputState::time` the frame was shown at.
+    shown_at: f64,
+    paused: bool,
+    notice: Option<Result<String, String>>,
+}
+
+fn paint(ui: &mut egui::Ui, code: &QrCode) {
+    let modules = code.size() + 2 * qr::BORDER;
+    let side = ui.available_width().min(MAX_SIDE);
+    let module = side / modules as f32;
+    let (rect, _) = ui.allocate_exact_size(egui::vec2(side, side), egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 0.0, egui::Color32::WHITE);
+    for y in 0..code.size() {
+        for x in 0..code.size() {
+            if code.get_module(x, y) {
+                let min = rect.min
+                    + egui::vec2(
+                        (x + qr::BORDER) as f32 * module,
+                        (y + qr::BORDER) as f32 * module,
+                    );
+                painter.rect_filled(
+                    egui::Rect::from_min_size(min, egui::vec2(module, module)),
+                    0.0,
+                    egui::Color32::BLACK,
+                );
+            }
+        }
+    }
+}
+
+/// Write an exported file next to the app natively, download it on the web.
+fn save(name: &str, contents: &[u8], mime: &str) -> Result<String, String> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let _ = mime;
+        std::fs::write(name, contents).map_err(|e| format!("Can't write {name}: {e}"))?;
+        Ok(format!("Saved {name}"))
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        super::web::download(name, contents, mime)
+            .map_err(|e| format!("Download failed: {e:?}"))?;
+        Ok(format!("Downloaded {name}"))
+    }
+}
+
+impl QrView {
+    fn text(&self, args: &str, tx: &str) -> Result<String, String> {
+        let decoded = DexArgs::decode(args).map_err(|e| format!("The encoded args: {e}"));
+        match self.source {
+            QrSource::Args => decoded.map(|args| args.encode()),
+            QrSource::Address => {
+                let network = if self.testnet {
+                    Network::Testnet
+                } else {
+                    Network::Mainnet
+                };
+                dex_lock_script(&decoded?)
+                    .to_address(network)
+                    .map_err(|e| format!("No address: {e:?}"))
+            }
+            QrSource::Transaction if tx.trim().is_empty() => Err(
+                "Generate a cancel transaction under My Orders on the Order Book page first"
+                    .to_owned(),
+            ),
+            // compact, the pretty printed JSON takes a lot more frames
+            QrSource::Transaction => serde_json::from_str::<serde_json::Value>(tx)
+                .map(|tx| tx.to_string())
+                .map_err(|e| format!("Invalid transaction JSON: {e}")),
+        }
+    }
+
+    fn export(&self, code: &QrCode, frames: usize, svg: bool) -> Result<String, String> {
+        let mut name = self.source.file_stem().to_owned();
+        if frames > 1 {
+            name.push_str(&format!("-{}of{frames}", self.frame + 1));
+        }
+        if svg {
+            save(
+                &format!("{name}.svg"),
+                qr::to_svg(code).as_bytes(),
+                "image/svg+xml",
+            )
+        } else {
+            let png = qr::to_png(code, 8).map_err(|e| e.to_string())?;
+            save(&format!("{name}.png"), &png, "image/png")
+        }
+    }
+
+    /// `args` are the encoded args, `tx` the unsigned transaction JSON.
+    pub fn ui(&mut self, ui: &mut egui::Ui, args: &str, tx: &str) {
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut self.source, QrSource::Args, "Args");
+            ui.selectable_value(&mut self.source, QrSource::Address, "Dex Lock Address");
+            ui.selectable_value(
+                &mut self.source,
+                QrSource::Transaction,
+                "Unsigned Transaction",
+            );
+            if self.source == QrSource::Address {
+                ui.separator();
+                ui.checkbox(&mut self.testnet, "Testnet");
+            }
+        });
+        let text = match self.text(args, tx) {
+            Ok(text) => text,
+            Err(e) => {
+                ui.label(egui::RichText::new(e).color(egui::Color32::PLACEHOLDER));
+                return;
+            }
+        };
+        if self.cache.as_ref().map(|(cached, _)| cached) != Some(&text) {
+            let codes = qr::frames(&text)
+                .iter()
+                .map(|frame| qr::encode(frame))
+                .collect();
+            self.cache = Some((text.clone(), codes));
+            self.frame = 0;
+            self.notice = None;
+        }
+        let codes = match &self.cache {
+            Some((_, Ok(codes))) => codes,
+            Some((_, Err(e))) => {
+                ui.label(egui::RichText::new(e.to_string()).color(egui::Color32::RED));
+                return;
+            }
+            None => unreachable!("just cached"),
+        };
+
+        let frames = codes.len();
+        let now = ui.input(|i| i.time);
+        if frames > 1 && !self.paused {
+            if now - self.shown_at >= FRAME_SECS {
+                self.frame = (self.frame + 1) % frames;
+                self.shown_at = now;
+            }
+            ui.ctx()
+                .request_repaint_after(std::time::Duration::from_secs_f64(FRAME_SECS));
+        }
+        self.frame = self.frame.min(frames - 1);
+        let code = codes[self.frame].clone();
+        paint(ui, &code);
+
+        if frames > 1 {
+            ui.horizontal(|ui| {
+                ui.label(format!(
+                    "Frame {} of {frames}, {} bytes in {}-byte frames",
+                    self.frame + 1,
+                    text.len(),
+                    qr::MAX_FRAME_LEN
+                ));
+                let pause = if self.paused { "Play" } else { "Pause" };
+                if ui.button(pause).clicked() {
+                    self.paused = !self.paused;
+                }
+                if ui.button("Previous").clicked() {
+                    self.paused = true;
+                    self.frame = (self.frame + frames - 1) % frames;
+                }
+                if ui.button("Next").clicked() {
+                    self.paused = true;
+                    self.frame = (self.frame + 1) % frames;
+                }
+            });
+        }
+        ui.horizontal(|ui| {
+            ui.label("Encodes");
+            copyable(ui, &text);
+        });
+        let mut export = None;
+        ui.horizontal(|ui| {
+            if ui.button("Export PNG").clicked() {
+                export = Some(false);
+            }
+            if ui.button("Export SVG").clicked() {
+                export = Some(true);
+            }
+            if frames > 1 {
+                ui.label(
+                    egui::RichText::new("exports the frame shown")
+                        .color(egui::Color32::PLACEHOLDER),
+                );
+            }
+        });
+        if let Some(svg) = export {
+            self.notice = Some(self.export(&code, frames, svg));
+        }
+        match &self.notice {
+            Some(Ok(notice)) => {
+                ui.colored_label(egui::Color32::GREEN, notice);
+            }
+            Some(Err(e)) => {
+                ui.colored_label(egui::Color32::RED, e);
+            }
+            None => {}
+        }
+    }
+}
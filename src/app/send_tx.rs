@@ -0,0 +1,173 @@
+use poll_promise::Promise;
+
+use crate::dex::lock_error_message;
+use crate::rpc::{RpcClient, RpcError};
+use crate::tx::parse_transaction_json;
+
+#[derive(Clone, Debug)]
+enum SendResult {
+    /// Scripts ran fine, `tx_hash` is set when the transaction was also sent.
+    Ok {
+        cycles: u64,
+        tx_hash: Option<String>,
+    },
+    DryRunFailed(RpcError),
+    /// Dry run passed but the node refused the transaction (fee, pool, double spend...).
+    SendFailed {
+        cycles: u64,
+        error: RpcError,
+    },
+}
+
+/// Dry run and broadcast a signed transaction.
+#[derive(Default)]
+pub struct SendTx {
+    tx_json: String,
+    running: Option<Promise<SendResult>>,
+    result: Option<SendResult>,
+    parse_error: Option<String>,
+}
+
+impl SendTx {
+    fn start(&mut self, ctx: &egui::Context, rpc_url: &str, send: bool) {
+        let tx = match parse_transaction_json(&self.tx_json) {
+            Ok(tx) => tx,
+            Err(e) => {
+                self.parse_error = Some(e.to_string());
+                return;
+            }
+        };
+        self.parse_error = None;
+        self.result = None;
+
+        let (sender, promise) = Promise::new();
+        let ctx = ctx.clone();
+        let client = RpcClient::new(rpc_url.trim());
+        let send_client = client.clone();
+        let send_tx = tx.clone();
+        client.estimate_cycles(&tx, move |result| {
+            let cycles = match result {
+                Ok(cycles) => cycles,
+                Err(e) => {
+                    sender.send(SendResult::DryRunFailed(e));
+                    return ctx.request_repaint();
+                }
+            };
+            if !send {
+                sender.send(SendResult::Ok {
+                    cycles,
+                    tx_hash: None,
+                });
+                return ctx.request_repaint();
+            }
+            send_client.send_transaction(&send_tx, move |result| {
+                sender.send(match result {
+                    Ok(tx_hash) => SendResult::Ok {
+                        cycles,
+                        tx_hash: Some(tx_hash),
+                    },
+                    Err(error) => SendResult::SendFailed { cycles, error },
+                });
+                ctx.request_repaint();
+            });
+        });
+        self.running = Some(promise);
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui, rpc_url: &str) {
+        if let Some(promise) = self.running.take() {
+            match promise.try_take() {
+                Ok(result) => self.result = Some(result),
+                Err(promise) => self.running = Some(promise),
+            }
+        }
+
+        ui.label(
+            egui::RichText::new(
+                "Paste a signed transaction (JSON, bare or as {\"transaction\": ...})",
+            )
+            .color(egui::Color32::PLACEHOLDER),
+        );
+        ui.add(
+            egui::TextEdit::multiline(&mut self.tx_json)
+                .code_editor()
+                .desired_rows(6)
+                .desired_width(f32::INFINITY),
+        );
+        ui.horizontal(|ui| {
+            let running = self.running.is_some();
+            if ui
+                .add_enabled(!running, egui::Button::new("Dry Run"))
+                .on_hover_text("Run all scripts on the node without sending")
+                .clicked()
+            {
+                self.start(ui.ctx(), rpc_url, false);
+            }
+            if ui
+                .add_enabled(!running, egui::Button::new("Send"))
+                .on_hover_text("Dry run first, then send_transaction if every script passes")
+                .clicked()
+            {
+                self.start(ui.ctx(), rpc_url, true);
+            }
+            if running {
+                ui.spinner();
+            }
+        });
+
+        if let Some(e) = &self.parse_error {
+            ui.label(
+                egui::RichText::new(format!("Invalid transaction JSON: {e}"))
+                    .color(egui::Color32::RED),
+            );
+        }
+        match &self.result {
+            None => {}
+            Some(SendResult::Ok { cycles, tx_hash }) => {
+                ui.label(
+                    egui::RichText::new(format!("Dry run passed, {cycles} cycles"))
+                        .color(egui::Color32::GREEN),
+                );
+                if let Some(tx_hash) = tx_hash {
+                    ui.horizontal(|ui| {
+                        ui.label(egui::RichText::new("Sent:").color(egui::Color32::GREEN));
+                        ui.hyperlink_to(
+                            tx_hash,
+                            format!("https://explorer.nervos.org/transaction/{tx_hash}"),
+                        );
+                    });
+                }
+            }
+            Some(SendResult::DryRunFailed(error)) => {
+                ui.label(egui::RichText::new("Dry run failed, not sent").color(egui::Color32::RED));
+                error_ui(ui, error);
+            }
+            Some(SendResult::SendFailed { cycles, error }) => {
+                ui.label(
+                    egui::RichText::new(format!(
+                        "Dry run passed ({cycles} cycles) but send_transaction failed"
+                    ))
+                    .color(egui::Color32::RED),
+                );
+                error_ui(ui, error);
+            }
+        }
+    }
+}
+
+fn error_ui(ui: &mut egui::Ui, error: &RpcError) {
+    if let Some(failure) = error.script_failure() {
+        let explanation = match lock_error_message(failure.exit_code) {
+            Some(meaning) => format!(
+                "{} exited with {}: {meaning}",
+                failure.source, failure.exit_code
+            ),
+            None => format!(
+                "{}: script exited with code {}",
+                failure.source, failure.exit_code
+            ),
+        };
+        ui.label(egui::RichText::new(explanation).color(egui::Color32::LIGHT_YELLOW));
+    }
+    ui.label(egui::RichText::new(error.to_string()).color(egui::Color32::RED));
+}
@@ -0,0 +1,216 @@
+use std::collections::BTreeMap;
+
+use poll_promise::Promise;
+
+use super::address_book::AddressBook;
+use super::order_book::{copyable, type_script_label};
+use crate::rpc::{format_ckb, IndexerTx, Pagination, RpcClient, RpcError, SearchKey};
+use crate::trade::{fetch_trade, Fill, Trade};
+
+const PAGE_SIZE: u32 = 50;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum GroupBy {
+    #[default]
+    TypeScript,
+    Owner,
+}
+
+/// Dex cells consumed on chain, found through `get_transactions` on the dex lock.
+#[derive(Default)]
+pub struct TradeHistory {
+    cursor: Option<String>,
+    exhausted: bool,
+    page: Option<Promise<Result<Pagination<IndexerTx>, RpcError>>>,
+    /// One promise per consumed dex cell of the pages loaded so far.
+    pending: Vec<Promise<Result<Option<Trade>, RpcError>>>,
+    trades: Vec<Trade>,
+    errors: Vec<RpcError>,
+    group_by: GroupBy,
+    owner_filter: String,
+}
+
+impl TradeHistory {
+    pub fn is_fetching(&self) -> bool {
+        self.page.is_some() || !self.pending.is_empty()
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self {
+            group_by: self.group_by,
+            owner_filter: std::mem::take(&mut self.owner_filter),
+            ..Default::default()
+        };
+    }
+
+    pub fn fetch_next_page(&mut self, ctx: &egui::Context, rpc_url: &str) {
+        let (sender, promise) = Promise::new();
+        let ctx = ctx.clone();
+        RpcClient::new(rpc_url.trim()).get_transactions(
+            &SearchKey::dex_lock("0x"),
+            PAGE_SIZE,
+            self.cursor.as_deref(),
+            move |result| {
+                sender.send(result);
+                ctx.request_repaint();
+            },
+        );
+        self.page = Some(promise);
+    }
+
+    fn poll(&mut self, ctx: &egui::Context, rpc_url: &str) {
+        if let Some(promise) = self.page.take() {
+            match promise.try_take() {
+                Err(promise) => self.page = Some(promise),
+                Ok(Err(e)) => self.errors.push(e),
+                Ok(Ok(page)) => {
+                    self.exhausted = page.objects.len() < PAGE_SIZE as usize;
+                    self.cursor = Some(page.last_cursor);
+                    let client = RpcClient::new(rpc_url.trim());
+                    // Outputs are offers being made, only inputs consume an offer.
+                    for record in page.objects.into_iter().filter(|r| r.io_type == "input") {
+                        let (sender, promise) = Promise::new();
+                        let ctx = ctx.clone();
+                        fetch_trade(&client, record, move |result| {
+                            sender.send(result);
+                            ctx.request_repaint();
+                        });
+                        self.pending.push(promise);
+                    }
+                }
+            }
+        }
+
+        let mut pending = vec![];
+        for promise in self.pending.drain(..) {
+            match promise.try_take() {
+                Err(promise) => pending.push(promise),
+                Ok(Ok(Some(trade))) => self.trades.push(trade),
+                Ok(Ok(None)) => {}
+                Ok(Err(e)) => self.errors.push(e),
+            }
+        }
+        self.pending = pending;
+        self.trades
+            .sort_by_key(|t| std::cmp::Reverse(t.block_number));
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui, rpc_url: &str, address_book: &AddressBook) {
+        self.poll(ui.ctx(), rpc_url);
+
+        ui.horizontal(|ui| {
+            let text = if self.cursor.is_some() {
+                "Load More"
+            } else {
+                "Load"
+            };
+            if ui
+                .add_enabled(
+                    !self.is_fetching() && !self.exhausted,
+                    egui::Button::new(text),
+                )
+                .clicked()
+            {
+                self.fetch_next_page(ui.ctx(), rpc_url);
+            }
+            if self.is_fetching() {
+                ui.spinner();
+            }
+            let taken = self
+                .trades
+                .iter()
+                .filter(|t| matches!(t.fill, Fill::Taken { .. }))
+                .count();
+            ui.label(format!(
+                "{} taken, {} cancelled{}",
+                taken,
+                self.trades.len() - taken,
+                if self.exhausted { ", all loaded" } else { "" }
+            ));
+        });
+        ui.horizontal(|ui| {
+            ui.label("Group by");
+            ui.selectable_value(&mut self.group_by, GroupBy::TypeScript, "Type Script");
+            ui.selectable_value(&mut self.group_by, GroupBy::Owner, "Owner");
+            ui.separator();
+            ui.label("Owner filter");
+            ui.add(
+                egui::TextEdit::singleline(&mut self.owner_filter)
+                    .hint_text("owner lock hash prefix"),
+            );
+        });
+        for e in &self.errors {
+            ui.label(egui::RichText::new(e.to_string()).color(egui::Color32::RED));
+        }
+
+        let owner_filter = self.owner_filter.trim().to_lowercase();
+        let mut groups: BTreeMap<String, Vec<&Trade>> = BTreeMap::new();
+        for trade in &self.trades {
+            let owner = trade.args.owner_script_hash_hex();
+            if !owner.starts_with(&owner_filter) && !owner[2..].starts_with(&owner_filter) {
+                continue;
+            }
+            let label = match self.group_by {
+                GroupBy::TypeScript => type_script_label(&trade.cell),
+                GroupBy::Owner => format!("Owner: {}", address_book.describe(&owner)),
+            };
+            groups.entry(label).or_default().push(trade);
+        }
+
+        for (label, trades) in groups {
+            egui::CollapsingHeader::new(format!("{label} ({} trades)", trades.len()))
+                .id_source(("trade_history", &label))
+                .default_open(true)
+                .show(ui, |ui| {
+                    egui::Grid::new(("trade_history_grid", &label))
+                        .striped(true)
+                        .num_columns(7)
+                        .show(ui, |ui| {
+                            for header in [
+                                "Block",
+                                "Result",
+                                "Price per unit",
+                                "Amount",
+                                "Paid",
+                                "Owner",
+                                "Transaction",
+                            ] {
+                                ui.label(egui::RichText::new(header).strong());
+                            }
+                            ui.end_row();
+                            for trade in trades {
+                                trade_row(ui, trade, address_book);
+                                ui.end_row();
+                            }
+                        });
+                });
+        }
+    }
+}
+
+fn trade_row(ui: &mut egui::Ui, trade: &Trade, address_book: &AddressBook) {
+    ui.label(trade.block_number.to_string());
+    match trade.fill {
+        Fill::Taken { .. } => ui.label(egui::RichText::new("Taken").color(egui::Color32::GREEN)),
+        Fill::Cancelled => {
+            ui.label(egui::RichText::new("Cancelled").color(egui::Color32::LIGHT_YELLOW))
+        }
+    };
+    let unit_price = trade
+        .args
+        .unit_price()
+        .map(|p| format!("{} CKB", format_ckb(p)))
+        .unwrap_or_else(|| "overflow".to_owned());
+    ui.label(unit_price);
+    match (trade.args.mode, trade.amount) {
+        (0, Some(amount)) => ui.label(amount.to_string()),
+        (0, None) => ui.label(egui::RichText::new("no amount").color(egui::Color32::RED)),
+        _ => ui.label("1 cell"),
+    };
+    match trade.fill {
+        Fill::Taken { paid } => ui.label(format!("{} CKB", format_ckb(paid))),
+        Fill::Cancelled => ui.label("-"),
+    };
+    address_book.owner_ui(ui, &trade.args.owner_script_hash_hex());
+    copyable(ui, &trade.tx_hash);
+}
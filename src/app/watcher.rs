@@ -0,0 +1,251 @@
+use poll_promise::Promise;
+
+use super::order_book::copyable;
+use crate::rpc::{format_ckb, OutPoint, RpcClient, RpcError};
+use crate::trade::{fetch_offer_status, Fill, OfferStatus};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+enum WatchState {
+    Live,
+    Consumed,
+    /// The node doesn't know this out point (typo, other network...).
+    Unknown,
+}
+
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+struct WatchedOrder {
+    out_point: OutPoint,
+    lock_args: String,
+    state: WatchState,
+    #[serde(skip)]
+    last_error: Option<RpcError>,
+}
+
+/// A watched order leaving the order book.
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+struct WatchEvent {
+    out_point: OutPoint,
+    /// `None` when the consuming transaction wasn't found.
+    fill: Option<Fill>,
+    tx_hash: Option<String>,
+    block_number: Option<u64>,
+}
+
+impl WatchEvent {
+    fn describe(&self) -> String {
+        match self.fill {
+            Some(Fill::Taken { paid }) => format!(
+                "Order {} was taken, owner received {} CKB",
+                self.out_point,
+                format_ckb(paid)
+            ),
+            Some(Fill::Cancelled) => format!("Order {} was cancelled", self.out_point),
+            None => format!("Order {} was consumed", self.out_point),
+        }
+    }
+}
+
+/// Watched dex cells, polled over RPC until they are consumed.
+#[derive(serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct Watcher {
+    watched: Vec<WatchedOrder>,
+    history: Vec<WatchEvent>,
+    interval_secs: f64,
+    #[serde(skip)]
+    polls: Vec<(OutPoint, Promise<Result<OfferStatus, RpcError>>)>,
+    /// `egui::InputState::time` of the last poll.
+    #[serde(skip)]
+    last_poll: Option<f64>,
+    #[serde(skip)]
+    banner: Vec<String>,
+}
+
+impl Default for Watcher {
+    fn default() -> Self {
+        Self {
+            watched: vec![],
+            history: vec![],
+            interval_secs: 30.0,
+            polls: vec![],
+            last_poll: None,
+            banner: vec![],
+        }
+    }
+}
+
+impl Watcher {
+    pub fn is_watching(&self, out_point: &OutPoint) -> bool {
+        self.watched.iter().any(|w| &w.out_point == out_point)
+    }
+
+    pub fn watch(&mut self, out_point: OutPoint, lock_args: String) {
+        if !self.is_watching(&out_point) {
+            self.watched.push(WatchedOrder {
+                out_point,
+                lock_args,
+                state: WatchState::Live,
+                last_error: None,
+            });
+            // check the new one right away
+            self.last_poll = None;
+        }
+    }
+
+    /// Called every frame, whatever is on screen, so the watch keeps running.
+    pub fn poll(&mut self, ctx: &egui::Context, rpc_url: &str) {
+        let mut polls = vec![];
+        for (out_point, promise) in self.polls.drain(..) {
+            match promise.try_take() {
+                Err(promise) => polls.push((out_point, promise)),
+                Ok(result) => {
+                    let Some(order) = self.watched.iter_mut().find(|w| w.out_point == out_point)
+                    else {
+                        continue;
+                    };
+                    order.last_error = None;
+                    match result {
+                        Err(e) => order.last_error = Some(e),
+                        Ok(OfferStatus::Live) => order.state = WatchState::Live,
+                        Ok(OfferStatus::Unknown) => order.state = WatchState::Unknown,
+                        Ok(OfferStatus::Consumed(trade)) => {
+                            order.state = WatchState::Consumed;
+                            let event = WatchEvent {
+                                out_point,
+                                fill: trade.as_ref().map(|t| t.fill),
+                                tx_hash: trade.as_ref().map(|t| t.tx_hash.clone()),
+                                block_number: trade.as_ref().map(|t| t.block_number),
+                            };
+                            self.banner.push(event.describe());
+                            self.history.push(event);
+                        }
+                    }
+                }
+            }
+        }
+        self.polls = polls;
+
+        let now = ctx.input(|i| i.time);
+        let due = self
+            .last_poll
+            .map_or(true, |last| now - last >= self.interval_secs);
+        let live = self
+            .watched
+            .iter()
+            .filter(|w| w.state != WatchState::Consumed);
+        if due && self.polls.is_empty() {
+            let client = RpcClient::new(rpc_url.trim());
+            for order in live {
+                let (sender, promise) = Promise::new();
+                let ctx = ctx.clone();
+                fetch_offer_status(
+                    &client,
+                    order.out_point.clone(),
+                    &order.lock_args,
+                    move |result| {
+                        sender.send(result);
+                        ctx.request_repaint();
+                    },
+                );
+                self.polls.push((order.out_point.clone(), promise));
+            }
+            self.last_poll = Some(now);
+        }
+        if self.watched.iter().any(|w| w.state != WatchState::Consumed) {
+            let next = self.last_poll.unwrap_or(now) + self.interval_secs - now;
+            ctx.request_repaint_after(std::time::Duration::from_secs_f64(next.max(0.0)));
+        }
+    }
+
+    /// Notifications about consumed orders, until dismissed.
+    pub fn banner_ui(&mut self, ui: &mut egui::Ui) {
+        if self.banner.is_empty() {
+            return;
+        }
+        egui::Frame::group(ui.style())
+            .fill(ui.visuals().warn_fg_color.gamma_multiply(0.2))
+            .show(ui, |ui| {
+                for message in &self.banner {
+                    ui.label(egui::RichText::new(message).strong());
+                }
+                if ui.button("Dismiss").clicked() {
+                    self.banner.clear();
+                }
+            });
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Poll every");
+            ui.add(
+                egui::DragValue::new(&mut self.interval_secs)
+                    .clamp_range(5.0..=3600.0)
+                    .suffix(" s"),
+            );
+            if !self.polls.is_empty() {
+                ui.spinner();
+            }
+        });
+        if self.watched.is_empty() {
+            ui.label(
+                egui::RichText::new("Nothing watched, use Watch on a fetched cell or on My Orders")
+                    .color(egui::Color32::PLACEHOLDER),
+            );
+        }
+
+        let mut remove = None;
+        egui::Grid::new("watched_orders")
+            .striped(true)
+            .show(ui, |ui| {
+                for (i, order) in self.watched.iter().enumerate() {
+                    copyable(ui, &order.out_point.to_string());
+                    match order.state {
+                        WatchState::Live => {
+                            ui.label(egui::RichText::new("live").color(egui::Color32::GREEN))
+                        }
+                        WatchState::Consumed => ui.label(
+                            egui::RichText::new("consumed").color(egui::Color32::LIGHT_YELLOW),
+                        ),
+                        WatchState::Unknown => {
+                            ui.label(egui::RichText::new("unknown").color(egui::Color32::RED))
+                        }
+                    };
+                    match &order.last_error {
+                        Some(e) => {
+                            ui.label(egui::RichText::new(e.to_string()).color(egui::Color32::RED))
+                        }
+                        None => ui.label(""),
+                    };
+                    if ui.button("Remove").clicked() {
+                        remove = Some(i);
+                    }
+                    ui.end_row();
+                }
+            });
+        if let Some(i) = remove {
+            self.watched.remove(i);
+        }
+
+        if !self.history.is_empty() {
+            egui::CollapsingHeader::new(format!("Watch History ({})", self.history.len())).show(
+                ui,
+                |ui| {
+                    for event in self.history.iter().rev() {
+                        ui.horizontal(|ui| {
+                            ui.label(event.describe());
+                            if let Some(block_number) = event.block_number {
+                                ui.label(format!("at block {block_number}"));
+                            }
+                            if let Some(tx_hash) = &event.tx_hash {
+                                copyable(ui, tx_hash);
+                            }
+                        });
+                    }
+                    if ui.button("Clear History").clicked() {
+                        self.history.clear();
+                    }
+                },
+            );
+        }
+    }
+}
@@ -0,0 +1,56 @@
+//! Browser APIs eframe doesn't wrap.
+
+use poll_promise::Promise;
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+
+fn document() -> Result<web_sys::Document, JsValue> {
+    web_sys::window()
+        .and_then(|window| window.document())
+        .ok_or_else(|| JsValue::from_str("no document"))
+}
+
+/// Save `contents` through the browser's download.
+pub fn download(name: &str, contents: &[u8], mime: &str) -> Result<(), JsValue> {
+    let parts = js_sys::Array::of1(&js_sys::Uint8Array::from(contents));
+    let mut options = web_sys::BlobPropertyBag::new();
+    options.type_(mime);
+    let blob = web_sys::Blob::new_with_u8_array_sequence_and_options(&parts, &options)?;
+    let url = web_sys::Url::create_object_url_with_blob(&blob)?;
+    let anchor: web_sys::HtmlAnchorElement = document()?.create_element("a")?.dyn_into()?;
+    anchor.set_href(&url);
+    anchor.set_download(name);
+    anchor.click();
+    web_sys::Url::revoke_object_url(&url)
+}
+
+/// Let the user pick a file, resolves to its text.
+///
+/// Never resolves when the picker is cancelled.
+pub fn upload(ctx: &egui::Context) -> Result<Promise<Option<String>>, JsValue> {
+    let input: web_sys::HtmlInputElement = document()?.create_element("input")?.dyn_into()?;
+    input.set_type("file");
+    input.set_accept(".json,application/json");
+    let (sender, promise) = Promise::new();
+    let ctx = ctx.clone();
+    let on_change = Closure::once_into_js(move |event: web_sys::Event| {
+        let file = event
+            .target()
+            .and_then(|target| target.dyn_into::<web_sys::HtmlInputElement>().ok())
+            .and_then(|input| input.files())
+            .and_then(|files| files.get(0));
+        wasm_bindgen_futures::spawn_local(async move {
+            let text = match file {
+                Some(file) => wasm_bindgen_futures::JsFuture::from(file.text())
+                    .await
+                    .ok()
+                    .and_then(|text| text.as_string()),
+                None => None,
+            };
+            sender.send(text);
+            ctx.request_repaint();
+        });
+    });
+    input.set_onchange(Some(on_change.unchecked_ref()));
+    input.click();
+    Ok(promise)
+}
@@ -0,0 +1,317 @@
+use poll_promise::Promise;
+use serde_json::{json, Map, Value};
+
+use super::TemplateApp;
+
+/// Version of the saved state. Bump it and add a migration whenever a change
+/// to `TemplateApp` would otherwise drop or misread what older builds saved.
+const STATE_VERSION: u64 = 1;
+
+/// Storage key of the versioned JSON state. `eframe::APP_KEY` holds the
+/// unversioned (version 0) state of older builds.
+const STATE_KEY: &str = "dex_helper_state";
+
+const EXPORT_FILE_NAME: &str = "dex-helper-workspace.json";
+
+/// `MIGRATIONS[n]` turns version `n` state into version `n + 1`.
+const MIGRATIONS: [fn(&mut Map<String, Value>); STATE_VERSION as usize] = [drafts_from_fields];
+
+/// Version 0 had a single order, in the top level fields.
+fn drafts_from_fields(state: &mut Map<String, Value>) {
+    let has_drafts = state
+        .get("drafts")
+        .and_then(|drafts| drafts.get("drafts"))
+        .and_then(Value::as_array)
+        .is_some_and(|drafts| !drafts.is_empty());
+    if has_drafts {
+        return;
+    }
+    let mut draft = Map::new();
+    draft.insert("name".to_owned(), "Order 1".into());
+    for key in [
+        "mode",
+        "owner_script_hash",
+        "amount",
+        "price_base",
+        "price_pow",
+        "encoded_string",
+    ] {
+        if let Some(value) = state.get(key) {
+            draft.insert(key.to_owned(), value.clone());
+        }
+    }
+    state.insert(
+        "drafts".to_owned(),
+        json!({ "drafts": [draft], "active": 0 }),
+    );
+}
+
+/// The whole workspace as JSON, with its version.
+pub fn to_json(app: &TemplateApp) -> String {
+    let mut state = serde_json::to_value(app).expect("the app state always serializes");
+    state["version"] = STATE_VERSION.into();
+    serde_json::to_string_pretty(&state).expect("a JSON value always serializes")
+}
+
+/// Read a workspace saved by this or an older version.
+pub fn from_json(json: &str) -> Result<TemplateApp, String> {
+    let state = serde_json::from_str(json).map_err(|e| format!("Not JSON: {e}"))?;
+    from_value(state)
+}
+
+fn from_value(state: Value) -> Result<TemplateApp, String> {
+    let Value::Object(mut state) = state else {
+        return Err("The workspace must be a JSON object".to_owned());
+    };
+    let version = match state.remove("version") {
+        None => 0,
+        Some(version) => version
+            .as_u64()
+            .ok_or_else(|| format!("Invalid version {version}"))?,
+    };
+    if version > STATE_VERSION {
+        return Err(format!(
+            "The workspace is from a newer version ({version}), this build reads up to {STATE_VERSION}"
+        ));
+    }
+    for migrate in &MIGRATIONS[version as usize..] {
+        migrate(&mut state);
+    }
+    serde_json::from_value(Value::Object(state)).map_err(|e| format!("Invalid workspace: {e}"))
+}
+
+/// State saved by the last run, migrated to the current version.
+pub fn load(storage: &dyn eframe::Storage) -> Option<TemplateApp> {
+    if let Some(json) = storage.get_string(STATE_KEY) {
+        return from_json(&json)
+            .map_err(|e| log::warn!("Ignoring the saved state: {e}"))
+            .ok();
+    }
+    let legacy = storage.get_string(eframe::APP_KEY)?;
+    from_legacy_ron(&legacy)
+        .map_err(|e| log::warn!("Ignoring the state of an older version: {e}"))
+        .ok()
+}
+
+/// Read the unversioned state older builds saved as RON under
+/// `eframe::APP_KEY`, through the same migrations as version 0 JSON.
+fn from_legacy_ron(ron: &str) -> Result<TemplateApp, String> {
+    let state: ron::Value = ron::from_str(ron).map_err(|e| format!("Not RON: {e}"))?;
+    let mut state = serde_json::to_value(state).map_err(|e| format!("Invalid state: {e}"))?;
+    if let Value::Object(state) = &mut state {
+        // unit enum variants lose their name in a `ron::Value`, let those fields default
+        state.retain(|_, value| !value.is_null());
+    }
+    from_value(state)
+}
+
+pub fn save(app: &TemplateApp, storage: &mut dyn eframe::Storage) {
+    storage.set_string(STATE_KEY, to_json(app));
+}
+
+/// File > Export/Import.
+pub struct Workspace {
+    /// File exported to and imported from, natively.
+    path: String,
+    window_open: bool,
+    notice: Option<Result<String, String>>,
+    /// A file picked on the web, `None` when it couldn't be read.
+    upload: Option<Promise<Option<String>>>,
+}
+
+impl Default for Workspace {
+    fn default() -> Self {
+        Self {
+            path: EXPORT_FILE_NAME.to_owned(),
+            window_open: false,
+            notice: None,
+            upload: None,
+        }
+    }
+}
+
+impl TemplateApp {
+    /// Replace everything with an imported workspace.
+    fn import_workspace(&mut self, json: &str) -> Result<(), String> {
+        let mut imported = from_json(json)?;
+        // not saved, they describe this session: the window and the page URL
+        imported.workspace = std::mem::take(&mut self.workspace);
+        imported.share_base = std::mem::take(&mut self.share_base);
+        // the link in the URL was already opened, don't open it again into the import
+        imported.last_fragment = self.last_fragment.take();
+        *self = imported;
+        self.restore();
+        Ok(())
+    }
+
+    fn export_workspace(&self) -> Result<String, String> {
+        let json = to_json(self);
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            std::fs::write(&self.workspace.path, json)
+                .map_err(|e| format!("Can't write {}: {e}", self.workspace.path))?;
+            Ok(format!("Exported to {}", self.workspace.path))
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            super::web::download(EXPORT_FILE_NAME, json.as_bytes(), "application/json")
+                .map_err(|e| format!("Download failed: {e:?}"))?;
+            Ok(format!("Downloaded {EXPORT_FILE_NAME}"))
+        }
+    }
+
+    fn import_workspace_file(&mut self, ctx: &egui::Context) -> Option<Result<String, String>> {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let _ = ctx;
+            let path = self.workspace.path.clone();
+            let result = std::fs::read_to_string(&path)
+                .map_err(|e| format!("Can't read {path}: {e}"))
+                .and_then(|json| self.import_workspace(&json))
+                .map(|()| format!("Imported {path}"));
+            Some(result)
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            match super::web::upload(ctx) {
+                Ok(promise) => {
+                    self.workspace.upload = Some(promise);
+                    None
+                }
+                Err(e) => Some(Err(format!("Upload failed: {e:?}"))),
+            }
+        }
+    }
+
+    /// Entries of the File menu.
+    pub(super) fn workspace_menu(&mut self, ui: &mut egui::Ui) {
+        if ui.button("Export/Import Workspace…").clicked() {
+            self.workspace.window_open = true;
+            ui.close_menu();
+        }
+    }
+
+    /// The export/import window, and imports of dropped or uploaded files.
+    pub(super) fn workspace_ui(&mut self, ctx: &egui::Context) {
+        let dropped = ctx.input(|i| i.raw.dropped_files.clone());
+        for file in dropped {
+            let json = match (&file.bytes, &file.path) {
+                (Some(bytes), _) => Ok(String::from_utf8_lossy(bytes).into_owned()),
+                (None, Some(path)) => std::fs::read_to_string(path)
+                    .map_err(|e| format!("Can't read {}: {e}", path.display())),
+                (None, None) => continue,
+            };
+            let notice = json
+                .and_then(|json| self.import_workspace(&json))
+                .map(|()| format!("Imported the dropped {}", file.name));
+            self.workspace.notice = Some(notice);
+            self.workspace.window_open = true;
+        }
+        if let Some(promise) = self.workspace.upload.take() {
+            match promise.try_take() {
+                Err(promise) => self.workspace.upload = Some(promise),
+                Ok(None) => self.workspace.notice = Some(Err("Can't read the file".to_owned())),
+                Ok(Some(json)) => {
+                    let notice = self
+                        .import_workspace(&json)
+                        .map(|()| "Imported the uploaded workspace".to_owned());
+                    self.workspace.notice = Some(notice);
+                }
+            }
+        }
+
+        let mut open = self.workspace.window_open;
+        egui::Window::new("Workspace")
+            .open(&mut open)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "All drafts, the address book, watched orders and settings as JSON (version {STATE_VERSION})."
+                ));
+                if !cfg!(target_arch = "wasm32") {
+                    ui.horizontal(|ui| {
+                        ui.label("File");
+                        ui.text_edit_singleline(&mut self.workspace.path);
+                    });
+                }
+                ui.horizontal(|ui| {
+                    if ui.button("Export").clicked() {
+                        self.workspace.notice = Some(self.export_workspace());
+                    }
+                    if ui
+                        .button("Import")
+                        .on_hover_text("Replaces the current workspace")
+                        .clicked()
+                    {
+                        if let Some(notice) = self.import_workspace_file(ctx) {
+                            self.workspace.notice = Some(notice);
+                        }
+                    }
+                });
+                ui.label(
+                    egui::RichText::new("A workspace file can also be dropped onto the window")
+                        .color(egui::Color32::PLACEHOLDER),
+                );
+                match &self.workspace.notice {
+                    Some(Ok(notice)) => {
+                        ui.colored_label(egui::Color32::GREEN, notice);
+                    }
+                    Some(Err(e)) => {
+                        ui.colored_label(egui::Color32::RED, e);
+                    }
+                    None => {}
+                }
+            });
+        self.workspace.window_open = open;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ARGS: &str =
+        "0x010029e560749fc5c785e3c1cfc3e14c0e608ec87120f0c066facf023ee8bd569d846400000008000000";
+
+    #[test]
+    fn loads_the_first_release_state() {
+        // what the first release saved, `owner_script_hash` wasn't persisted
+        let ron = format!(
+            r#"(price_base:100,price_pow:8,mode:1,amount:5,encoded_string:"{ARGS}",encode_status:"",decode_status:"Decode success",last_status:true,ckb_cap:100.0)"#
+        );
+        let app = from_legacy_ron(&ron).unwrap();
+        let draft = app.drafts.active();
+        assert_eq!(draft.name, "Order 1");
+        assert_eq!(draft.mode, 1);
+        assert_eq!(draft.amount, 5);
+        assert_eq!(draft.price_base, 100);
+        assert_eq!(draft.price_pow, 8);
+        assert_eq!(draft.encoded_string, ARGS);
+    }
+
+    #[test]
+    fn round_trips_and_rejects_newer_versions() {
+        let app = from_legacy_ron(&format!(r#"(encoded_string:"{ARGS}")"#)).unwrap();
+        let json = to_json(&app);
+        let loaded = from_json(&json).unwrap();
+        assert_eq!(loaded.drafts.active().encoded_string, ARGS);
+
+        let mut state: Value = serde_json::from_str(&json).unwrap();
+        state["version"] = (STATE_VERSION + 1).into();
+        assert!(from_value(state).is_err());
+        assert!(from_json("[]").is_err());
+    }
+
+    #[test]
+    fn import_keeps_the_session() {
+        let mut app = TemplateApp {
+            share_base: "https://example.com/dex/".to_owned(),
+            last_fragment: Some(format!("#decode={ARGS}")),
+            ..Default::default()
+        };
+        let exported = to_json(&TemplateApp::default());
+        app.import_workspace(&exported).unwrap();
+        assert_eq!(app.share_base, "https://example.com/dex/");
+        assert_eq!(app.last_fragment, Some(format!("#decode={ARGS}")));
+    }
+}
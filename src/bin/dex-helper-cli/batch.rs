@@ -0,0 +1,336 @@
+//! `batch`: decode or encode many orders at once, for audits.
+//!
+//! Every input record gives one output record, failed ones carry the error
+//! instead of aborting the whole run.
+
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+
+use dex_helper::dex::{parse_owner_script_hash, DexArgs, DexHelperError, MAX_MODE};
+
+use super::{Args, CliError};
+
+/// `EX_DATAERR` from sysexits.h, some records failed.
+pub const EXIT_DATA: i32 = 65;
+
+const COLUMNS: [&str; 8] = [
+    "line",
+    "args",
+    "mode",
+    "owner_script_hash",
+    "price_base",
+    "price_pow",
+    "error",
+    "code",
+];
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Csv,
+    Jsonl,
+}
+
+impl Format {
+    fn parse(s: &str) -> Result<Self, CliError> {
+        match s {
+            "csv" => Ok(Self::Csv),
+            "jsonl" | "ndjson" => Ok(Self::Jsonl),
+            _ => Err(CliError::Usage(format!("unknown format {s}"))),
+        }
+    }
+}
+
+/// An input record, field names are normalized to the output column names.
+struct Record {
+    line: usize,
+    fields: HashMap<String, String>,
+}
+
+impl Record {
+    fn get(&self, name: &str) -> Option<&str> {
+        self.fields
+            .get(name)
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+    }
+
+    fn number<T: std::str::FromStr>(&self, name: &str) -> Result<T, CliError> {
+        let value = self
+            .get(name)
+            .ok_or_else(|| CliError::Data(format!("missing {name}")))?;
+        value
+            .parse()
+            .map_err(|_| CliError::Data(format!("invalid {name} {value}")))
+    }
+
+    /// Decode `args` when present, otherwise encode the order fields.
+    fn convert(&self) -> Result<DexArgs, CliError> {
+        if let Some(args) = self.get("args") {
+            return Ok(DexArgs::decode(args)?);
+        }
+        let mode = self.number("mode")?;
+        if mode > MAX_MODE {
+            return Err(DexHelperError::ModeTooBig.into());
+        }
+        let owner = self
+            .get("owner_script_hash")
+            .ok_or_else(|| CliError::Data("missing args or owner_script_hash".to_owned()))?;
+        Ok(DexArgs {
+            mode,
+            owner_script_hash: parse_owner_script_hash(owner)?,
+            price_base: self.number("price_base")?,
+            price_pow: self.number("price_pow")?,
+        })
+    }
+}
+
+fn column_name(name: &str) -> String {
+    match name.trim().to_lowercase().as_str() {
+        "owner" => "owner_script_hash".to_owned(),
+        "base" => "price_base".to_owned(),
+        "pow" => "price_pow".to_owned(),
+        name => name.to_owned(),
+    }
+}
+
+/// Split a CSV line, double quoted fields may contain commas and `""`.
+fn split_csv(line: &str) -> Vec<String> {
+    let mut fields = vec![];
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_owned()
+    }
+}
+
+/// A header names columns, a plain list of args starts right away with hex.
+fn is_header(values: &[String]) -> bool {
+    let first = values.first().map_or("", |s| s.trim());
+    let looks_like_args = first.starts_with("0x")
+        || !first.is_empty() && first.bytes().all(|b| b.is_ascii_hexdigit());
+    !looks_like_args
+}
+
+fn read_records(input: impl BufRead, format: Format) -> Result<Vec<Record>, CliError> {
+    let mut records = vec![];
+    let mut header: Option<Vec<String>> = None;
+    for (i, line) in input.lines().enumerate() {
+        let line = line.map_err(|e| CliError::Data(e.to_string()))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let line_number = i + 1;
+        let fields = match format {
+            Format::Csv => {
+                let values = split_csv(&line);
+                let header = match &header {
+                    Some(header) => header,
+                    None if is_header(&values) => {
+                        header = Some(values.iter().map(|name| column_name(name)).collect());
+                        continue;
+                    }
+                    // no header, a single implicit `args` column
+                    None => header.insert(vec!["args".to_owned()]),
+                };
+                header.iter().cloned().zip(values).collect()
+            }
+            Format::Jsonl => match serde_json::from_str::<serde_json::Value>(&line) {
+                Ok(serde_json::Value::String(args)) => HashMap::from([("args".to_owned(), args)]),
+                Ok(serde_json::Value::Object(object)) => object
+                    .into_iter()
+                    .filter_map(|(name, value)| {
+                        let value = match value {
+                            serde_json::Value::Null => return None,
+                            serde_json::Value::String(s) => s,
+                            value => value.to_string(),
+                        };
+                        Some((column_name(&name), value))
+                    })
+                    .collect(),
+                Ok(_) => HashMap::from([(
+                    "json_error".to_owned(),
+                    "expected an object or an args string".to_owned(),
+                )]),
+                // keep going, the record reports the error
+                Err(e) => HashMap::from([("json_error".to_owned(), e.to_string())]),
+            },
+        };
+        records.push(Record {
+            line: line_number,
+            fields,
+        });
+    }
+    Ok(records)
+}
+
+fn output_row(record: &Record) -> Vec<(&'static str, serde_json::Value)> {
+    let result = match record.fields.get("json_error") {
+        Some(e) => Err(CliError::Data(format!("invalid JSON: {e}"))),
+        None => record.convert(),
+    };
+    let mut row = vec![("line", record.line.into())];
+    match result {
+        Ok(args) => row.extend([
+            ("args", args.encode().into()),
+            ("mode", args.mode.into()),
+            ("owner_script_hash", args.owner_script_hash_hex().into()),
+            ("price_base", args.price_base.into()),
+            ("price_pow", args.price_pow.into()),
+        ]),
+        Err(e) => row.extend([
+            ("error", e.to_string().into()),
+            ("code", e.exit_code().into()),
+        ]),
+    }
+    row
+}
+
+/// Run `batch [FILE] [--format csv|jsonl]`, returns whether every record converted.
+pub fn run(mut args: Args, out: &mut impl Write) -> Result<bool, CliError> {
+    let format = args
+        .option("--format")?
+        .map(|f| Format::parse(&f))
+        .transpose()?;
+    let path = (!args.0.is_empty())
+        .then(|| args.positional("[FILE]"))
+        .transpose()?
+        .filter(|path| path != "-");
+    args.finish()?;
+
+    let input: Box<dyn BufRead> = match &path {
+        Some(path) => Box::new(std::io::BufReader::new(
+            std::fs::File::open(path).map_err(|e| CliError::Data(format!("{path}: {e}")))?,
+        )),
+        None => Box::new(std::io::stdin().lock()),
+    };
+    let from_extension = path
+        .as_deref()
+        .and_then(|path| path.rsplit_once('.'))
+        .and_then(|(_, extension)| Format::parse(extension).ok());
+    let format = format.or(from_extension).unwrap_or(Format::Csv);
+
+    let records = read_records(input, format)?;
+    let write_error = |e: std::io::Error| CliError::Data(e.to_string());
+    if format == Format::Csv {
+        writeln!(out, "{}", COLUMNS.join(",")).map_err(write_error)?;
+    }
+    let mut all_ok = true;
+    for record in &records {
+        let row = output_row(record);
+        all_ok &= !row.iter().any(|(name, _)| *name == "error");
+        let line = match format {
+            Format::Csv => {
+                let row: HashMap<_, _> = row.into_iter().collect();
+                COLUMNS
+                    .iter()
+                    .map(|column| match row.get(column) {
+                        Some(serde_json::Value::String(s)) => csv_field(s),
+                        Some(value) => value.to_string(),
+                        None => String::new(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(",")
+            }
+            Format::Jsonl => serde_json::Value::Object(
+                row.into_iter()
+                    .map(|(name, value)| (name.to_owned(), value))
+                    .collect(),
+            )
+            .to_string(),
+        };
+        writeln!(out, "{line}").map_err(write_error)?;
+    }
+    Ok(all_ok)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ARGS: &str =
+        "0x010029e560749fc5c785e3c1cfc3e14c0e608ec87120f0c066facf023ee8bd569d846400000008000000";
+
+    fn convert(input: &str, format: Format) -> Vec<Vec<(&'static str, serde_json::Value)>> {
+        read_records(input.as_bytes(), format)
+            .map_err(|e| e.to_string())
+            .unwrap()
+            .iter()
+            .map(output_row)
+            .collect()
+    }
+
+    fn field<'a>(
+        row: &'a [(&str, serde_json::Value)],
+        name: &str,
+    ) -> Option<&'a serde_json::Value> {
+        row.iter().find(|(n, _)| *n == name).map(|(_, value)| value)
+    }
+
+    #[test]
+    fn csv_with_header() {
+        let input = format!("mode,owner,base,pow\n1,{},100,8\nargs\n", &ARGS[6..70]);
+        let rows = convert(&input, Format::Csv);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(field(&rows[0], "args"), Some(&ARGS.into()));
+        assert_eq!(field(&rows[0], "line"), Some(&2.into()));
+        assert!(field(&rows[1], "error").is_some());
+    }
+
+    #[test]
+    fn csv_list_of_args_has_no_header() {
+        let bare = &ARGS[2..];
+        let rows = convert(&format!("{ARGS}\n\n{bare}\n0x12\n"), Format::Csv);
+        assert_eq!(rows.len(), 3);
+        assert_eq!(field(&rows[0], "line"), Some(&1.into()));
+        assert_eq!(field(&rows[0], "mode"), Some(&1.into()));
+        assert_eq!(field(&rows[1], "args"), Some(&ARGS.into()));
+        assert_eq!(
+            field(&rows[2], "code"),
+            Some(&DexHelperError::ArgsLenError.code().into())
+        );
+    }
+
+    #[test]
+    fn jsonl_objects_and_args_strings() {
+        let input = format!(
+            "{{\"args\": \"{ARGS}\"}}\n\"{ARGS}\"\n{{\"mode\": 3, \"owner\": \"0x00\"}}\n42\nnot json\n"
+        );
+        let rows = convert(&input, Format::Jsonl);
+        assert_eq!(rows.len(), 5);
+        assert_eq!(field(&rows[0], "args"), Some(&ARGS.into()));
+        assert_eq!(field(&rows[1], "args"), Some(&ARGS.into()));
+        assert_eq!(
+            field(&rows[2], "code"),
+            Some(&DexHelperError::ModeTooBig.code().into())
+        );
+        for row in &rows[3..] {
+            let error = field(row, "error").unwrap().as_str().unwrap();
+            assert!(error.starts_with("invalid JSON"), "{error}");
+        }
+    }
+
+    #[test]
+    fn quoted_csv_fields() {
+        assert_eq!(split_csv(r#"a,"b,c","d""e""#), ["a", "b,c", "d\"e"]);
+        assert_eq!(csv_field("b,c"), r#""b,c""#);
+    }
+}
@@ -0,0 +1,256 @@
+//! Headless access to the dex args codec, for scripts and CI.
+//!
+//! Exit status is 0 on success, [`DexHelperError::code`] on codec errors,
+//! [`EXIT_USAGE`] on bad command lines and [`batch::EXIT_DATA`] on bad input.
+
+mod batch;
+
+use dex_helper::dex::{
+    mode_description, parse_owner_script_hash, DexArgs, DexHelperError, MAX_MODE,
+};
+use dex_helper::diagnose::diagnose_args;
+use dex_helper::rpc::format_ckb;
+
+/// `EX_USAGE` from sysexits.h
+const EXIT_USAGE: i32 = 64;
+
+const USAGE: &str = "\
+Usage: dex-helper-cli <COMMAND> [--json]
+
+Commands:
+  encode --mode <0|1|2> --owner <HASH> --base <u32> --pow <u32>
+      Encode dex lock args
+  decode <ARGS>
+      Decode dex lock args
+  price --amount <u128> (--args <ARGS> | --mode <0|1|2> --base <u32> --pow <u32>)
+      Total payment of an order in shannons, --amount is ignored unless mode is 0
+  batch [FILE] [--format <csv|jsonl>]
+      Decode the `args` field, or encode the `mode`, `owner_script_hash`,
+      `price_base` and `price_pow` fields, of every record in FILE or stdin.
+      Failed records are written with an `error` field. Format defaults to
+      the FILE extension, or csv with a header line
+  serve [ADDR]
+      Serve the JSON API on ADDR, 127.0.0.1:8115 by default
+      (needs the `serve` feature)
+
+Options:
+  --json  Print machine readable JSON instead of text (not for batch)
+";
+
+enum CliError {
+    Usage(String),
+    Dex(DexHelperError),
+    /// Unreadable or malformed input.
+    Data(String),
+}
+
+impl From<DexHelperError> for CliError {
+    fn from(e: DexHelperError) -> Self {
+        Self::Dex(e)
+    }
+}
+
+impl CliError {
+    fn exit_code(&self) -> i32 {
+        match self {
+            Self::Usage(_) => EXIT_USAGE,
+            Self::Dex(e) => e.code(),
+            Self::Data(_) => batch::EXIT_DATA,
+        }
+    }
+}
+
+impl std::fmt::Display for CliError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Usage(e) => write!(f, "{e}"),
+            Self::Dex(e) => write!(f, "{e}"),
+            Self::Data(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+/// Command line arguments left to parse.
+struct Args(Vec<String>);
+
+impl Args {
+    fn flag(&mut self, name: &str) -> bool {
+        let len = self.0.len();
+        self.0.retain(|arg| arg != name);
+        self.0.len() != len
+    }
+
+    fn option(&mut self, name: &str) -> Result<Option<String>, CliError> {
+        let Some(i) = self.0.iter().position(|arg| arg == name) else {
+            return Ok(None);
+        };
+        if i + 1 >= self.0.len() {
+            return Err(CliError::Usage(format!("{name} needs a value")));
+        }
+        self.0.remove(i);
+        Ok(Some(self.0.remove(i)))
+    }
+
+    fn required<T: std::str::FromStr>(&mut self, name: &str) -> Result<T, CliError> {
+        let value = self
+            .option(name)?
+            .ok_or_else(|| CliError::Usage(format!("missing {name}")))?;
+        value
+            .parse()
+            .map_err(|_| CliError::Usage(format!("invalid {name} {value}")))
+    }
+
+    fn positional(&mut self, name: &str) -> Result<String, CliError> {
+        if self.0.is_empty() {
+            return Err(CliError::Usage(format!("missing {name}")));
+        }
+        Ok(self.0.remove(0))
+    }
+
+    fn finish(self) -> Result<(), CliError> {
+        match self.0.first() {
+            Some(arg) => Err(CliError::Usage(format!("unexpected argument {arg}"))),
+            None => Ok(()),
+        }
+    }
+}
+
+fn mode(args: &mut Args) -> Result<u16, CliError> {
+    let mode = args.required("--mode")?;
+    if mode > MAX_MODE {
+        return Err(DexHelperError::ModeTooBig.into());
+    }
+    Ok(mode)
+}
+
+fn print_args(args: &DexArgs, json: bool) {
+    if json {
+        let mut value = serde_json::to_value(args).expect("args always serialize");
+        value["args"] = args.encode().into();
+        println!("{value}");
+    } else {
+        println!("args: {}", args.encode());
+        println!("mode: {} ({})", args.mode, mode_description(args.mode));
+        println!("owner_script_hash: {}", args.owner_script_hash_hex());
+        println!("price_base: {}", args.price_base);
+        println!("price_pow: {}", args.price_pow);
+    }
+}
+
+fn run(command: &str, mut args: Args, json: bool) -> Result<(), CliError> {
+    match command {
+        "encode" => {
+            let dex_args = DexArgs {
+                mode: mode(&mut args)?,
+                owner_script_hash: parse_owner_script_hash(&args.required::<String>("--owner")?)?,
+                price_base: args.required("--base")?,
+                price_pow: args.required("--pow")?,
+            };
+            args.finish()?;
+            if json {
+                print_args(&dex_args, json);
+            } else {
+                println!("{}", dex_args.encode());
+            }
+        }
+        "decode" => {
+            let hex = args.positional("<ARGS>")?;
+            args.finish()?;
+            if !json {
+                for diagnostic in diagnose_args(hex.trim()) {
+                    eprintln!("{:?}: {}", diagnostic.severity, diagnostic.message);
+                }
+            }
+            let dex_args = DexArgs::decode(hex.trim())?;
+            print_args(&dex_args, json);
+        }
+        "price" => {
+            let amount: u128 = args.required("--amount")?;
+            let dex_args = match args.option("--args")? {
+                Some(hex) => DexArgs::decode(hex.trim())?,
+                None => DexArgs {
+                    mode: mode(&mut args)?,
+                    owner_script_hash: [0; 32],
+                    price_base: args.required("--base")?,
+                    price_pow: args.required("--pow")?,
+                },
+            };
+            args.finish()?;
+            let shannons = dex_args.checked_total_price(amount)?;
+            if json {
+                // u128 doesn't fit in a JSON number without losing precision
+                let value = serde_json::json!({
+                    "shannons": shannons.to_string(),
+                    "ckb": format_ckb(shannons),
+                });
+                println!("{value}");
+            } else {
+                println!("{shannons} shannons ({} CKB)", format_ckb(shannons));
+            }
+        }
+        "batch" => {
+            if json {
+                return Err(CliError::Usage(
+                    "batch takes --format instead of --json".to_owned(),
+                ));
+            }
+            if !batch::run(args, &mut std::io::stdout().lock())? {
+                return Err(CliError::Data("some records failed".to_owned()));
+            }
+        }
+        "serve" => serve(args)?,
+        _ => return Err(CliError::Usage(format!("unknown command {command}"))),
+    }
+    Ok(())
+}
+
+#[cfg(all(feature = "serve", not(target_arch = "wasm32")))]
+fn serve(mut args: Args) -> Result<(), CliError> {
+    let addr = match args.0.is_empty() {
+        true => "127.0.0.1:8115".to_owned(),
+        false => args.positional("[ADDR]")?,
+    };
+    args.finish()?;
+    env_logger::init();
+    let server = dex_helper::server::ApiServer::bind(&addr)
+        .map_err(|e| CliError::Data(format!("{addr}: {e}")))?;
+    eprintln!("Serving the dex helper API on {}", server.url());
+    loop {
+        std::thread::park();
+    }
+}
+
+#[cfg(not(all(feature = "serve", not(target_arch = "wasm32"))))]
+fn serve(_args: Args) -> Result<(), CliError> {
+    Err(CliError::Usage(
+        "serve needs dex-helper-cli built with --features serve".to_owned(),
+    ))
+}
+
+fn main() {
+    let mut args = Args(std::env::args().skip(1).collect());
+    let json = args.flag("--json");
+    if args.flag("--help") || args.flag("-h") {
+        print!("{USAGE}");
+        return;
+    }
+    if args.0.is_empty() {
+        eprint!("{USAGE}");
+        std::process::exit(EXIT_USAGE);
+    }
+    let command = args.0.remove(0);
+
+    if let Err(e) = run(&command, args, json) {
+        if json {
+            // on stderr too, stdout only ever carries results
+            let value = serde_json::json!({ "error": e.to_string(), "code": e.exit_code() });
+            eprintln!("{value}");
+        } else {
+            eprintln!("error: {e}");
+            if let CliError::Usage(_) = e {
+                eprint!("\n{USAGE}");
+            }
+        }
+        std::process::exit(e.exit_code());
+    }
+}
@@ -0,0 +1,304 @@
+/// Code hash of the deployed Generic Dex Lock (`hash_type` is [`DEX_LOCK_HASH_TYPE`]).
+pub const DEX_LOCK_CODE_HASH: &str =
+    "0x10d0d91b09a3ff3d6db5c6fc0dad9ba73b9a8d2d33a63b5a8f08224521d6db22";
+pub const DEX_LOCK_HASH_TYPE: &str = "type";
+/// Transaction that deployed the dex lock, output `0x0` is the code cell dep.
+pub const DEX_LOCK_DEP_TX_HASH: &str =
+    "0x3884356c08232eefd183fb7673937d778054ec2c7508e3f8273b6d1f4a23b12f";
+
+/// `mode(2) || owner_script_hash(32) || price_base(4) || price_pow(4)`
+pub const DEX_ARGS_LEN: usize = 42;
+pub const MAX_MODE: u16 = 2;
+/// Byte ranges of the args fields, in order.
+pub const DEX_ARGS_FIELDS: [(&str, std::ops::Range<usize>); 4] = [
+    ("mode", 0..2),
+    ("owner_script_hash", 2..34),
+    ("price_base", 34..38),
+    ("price_pow", 38..42),
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DexHelperError {
+    ArgsDecodeError,
+    LockScriptHashError,
+    ModeTooBig,
+    ArgsLenError,
+    /// The total price doesn't fit in a `u128` of shannons.
+    PriceOverflow,
+}
+
+impl DexHelperError {
+    /// Stable numeric code, used as the CLI exit status.
+    pub fn code(self) -> i32 {
+        match self {
+            Self::ArgsDecodeError => 1,
+            Self::LockScriptHashError => 2,
+            Self::ModeTooBig => 3,
+            Self::ArgsLenError => 4,
+            Self::PriceOverflow => 5,
+        }
+    }
+}
+
+impl std::fmt::Display for DexHelperError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ArgsDecodeError => write!(f, "Args are not valid hex"),
+            Self::LockScriptHashError => write!(f, "LockScript Hash must be 32 bytes of hex"),
+            Self::ModeTooBig => write!(f, "Mode must be 0, 1 or 2"),
+            Self::ArgsLenError => write!(f, "Args must be {DEX_ARGS_LEN} bytes"),
+            Self::PriceOverflow => write!(f, "Total price overflows"),
+        }
+    }
+}
+
+impl std::error::Error for DexHelperError {}
+
+pub fn mode_description(mode: u16) -> &'static str {
+    match mode {
+        0 => "UDT compatible mode",
+        1 => "Restrict Mode, Can't modify Data/Type during trade.",
+        2 => "Partial Restrict Mode, Can't modify Type during trade, but Data can.",
+        _ => "Unknown mode",
+    }
+}
+
+/// Decoded dex lock args.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct DexArgs {
+    pub mode: u16,
+    #[serde(with = "hex_0x")]
+    pub owner_script_hash: [u8; 32],
+    pub price_base: u32,
+    pub price_pow: u32,
+}
+
+impl DexArgs {
+    /// Decode args from a hex string, the `0x` prefix is optional.
+    pub fn decode(args: &str) -> Result<Self, DexHelperError> {
+        let args = hex::decode(strip_0x(args)).map_err(|_| DexHelperError::ArgsDecodeError)?;
+        Self::from_bytes(&args)
+    }
+
+    pub fn from_bytes(args: &[u8]) -> Result<Self, DexHelperError> {
+        if args.len() != DEX_ARGS_LEN {
+            return Err(DexHelperError::ArgsLenError);
+        }
+        let mode = u16::from_le_bytes([args[0], args[1]]);
+        if mode > MAX_MODE {
+            return Err(DexHelperError::ModeTooBig);
+        }
+        let mut owner_script_hash = [0u8; 32];
+        owner_script_hash.copy_from_slice(&args[2..34]);
+        let price_base = u32::from_le_bytes([args[34], args[35], args[36], args[37]]);
+        let price_pow = u32::from_le_bytes([args[38], args[39], args[40], args[41]]);
+        Ok(Self {
+            mode,
+            owner_script_hash,
+            price_base,
+            price_pow,
+        })
+    }
+
+    pub fn to_bytes(&self) -> [u8; DEX_ARGS_LEN] {
+        let mut args = [0u8; DEX_ARGS_LEN];
+        args[0..2].copy_from_slice(&self.mode.to_le_bytes());
+        args[2..34].copy_from_slice(&self.owner_script_hash);
+        args[34..38].copy_from_slice(&self.price_base.to_le_bytes());
+        args[38..42].copy_from_slice(&self.price_pow.to_le_bytes());
+        args
+    }
+
+    /// Encode args as a `0x` prefixed hex string.
+    pub fn encode(&self) -> String {
+        format!("0x{}", hex::encode(self.to_bytes()))
+    }
+
+    /// Shannons paid per unit, i.e. `price_base * 10^price_pow`.
+    ///
+    /// In UDT mode a unit is `10^8` of the raw `amount` stored in cell data,
+    /// in the other modes the whole cell is a single unit.
+    pub fn unit_price(&self) -> Option<u128> {
+        (self.price_base as u128).checked_mul(10u128.checked_pow(self.price_pow)?)
+    }
+
+    /// [`Self::total_price`] for callers reporting errors.
+    pub fn checked_total_price(&self, amount: u128) -> Result<u128, DexHelperError> {
+        self.total_price(amount)
+            .ok_or(DexHelperError::PriceOverflow)
+    }
+
+    /// Exact total payment in shannons, `None` on overflow.
+    pub fn total_price(&self, amount: u128) -> Option<u128> {
+        let unit_price = self.unit_price()?;
+        match self.mode {
+            0 => Some(amount.checked_mul(unit_price)? / 100_000_000),
+            _ => Some(unit_price),
+        }
+    }
+
+    pub fn owner_script_hash_hex(&self) -> String {
+        format!("0x{}", hex::encode(self.owner_script_hash))
+    }
+}
+
+/// Meaning of a script exit code, for decoding failed verifications.
+///
+/// Only the `ckb-std` syscall errors every contract built from the `ckb-std`
+/// template shares (`SysError` mapped to 1..=4 in its `error.rs`). The dex
+/// lock's own codes aren't listed, they'd need the contract's error enum, so
+/// `None` means the code has to be looked up in the contract source.
+pub fn lock_error_message(exit_code: i8) -> Option<&'static str> {
+    match exit_code {
+        1 => Some("IndexOutOfBound: a syscall read past the last cell"),
+        2 => Some("ItemMissing: a cell field the lock reads is missing"),
+        3 => Some("LengthNotEnough: a cell field is shorter than expected"),
+        4 => Some("Encoding: molecule data is malformed"),
+        _ => None,
+    }
+}
+
+/// Parse a 32 bytes lock script hash, the `0x` prefix is optional.
+pub fn parse_owner_script_hash(hash: &str) -> Result<[u8; 32], DexHelperError> {
+    let bytes = hex::decode(strip_0x(hash)).map_err(|_| DexHelperError::LockScriptHashError)?;
+    bytes
+        .try_into()
+        .map_err(|_| DexHelperError::LockScriptHashError)
+}
+
+/// Read the UDT amount (`u128` little endian) from the first 16 bytes of cell data.
+pub fn udt_amount(data: &[u8]) -> Option<u128> {
+    let amount: [u8; 16] = data.get(..16)?.try_into().ok()?;
+    Some(u128::from_le_bytes(amount))
+}
+
+pub fn strip_0x(s: &str) -> &str {
+    s.strip_prefix("0x").unwrap_or(s)
+}
+
+/// Serde for hashes as `0x` prefixed hex strings, like the CKB JSON-RPC.
+mod hex_0x {
+    pub fn serialize<S: serde::Serializer>(bytes: &[u8; 32], s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(&format!("0x{}", hex::encode(bytes)))
+    }
+
+    pub fn deserialize<'de, D: serde::Deserializer<'de>>(d: D) -> Result<[u8; 32], D::Error> {
+        let s: String = serde::Deserialize::deserialize(d)?;
+        super::parse_owner_script_hash(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OWNER: &str = "0x29e560749fc5c785e3c1cfc3e14c0e608ec87120f0c066facf023ee8bd569d84";
+
+    fn args(mode: u16, price_base: u32, price_pow: u32) -> DexArgs {
+        DexArgs {
+            mode,
+            owner_script_hash: parse_owner_script_hash(OWNER).unwrap(),
+            price_base,
+            price_pow,
+        }
+    }
+
+    #[test]
+    fn encode_decode_round_trip() {
+        for mode in 0..=MAX_MODE {
+            let args = args(mode, 100, 8);
+            let encoded = args.encode();
+            assert_eq!(encoded.len(), 2 + DEX_ARGS_LEN * 2);
+            assert_eq!(DexArgs::decode(&encoded), Ok(args.clone()));
+            // the prefix is optional
+            assert_eq!(DexArgs::decode(strip_0x(&encoded)), Ok(args.clone()));
+            assert_eq!(DexArgs::from_bytes(&args.to_bytes()), Ok(args));
+        }
+    }
+
+    #[test]
+    fn encodes_fields_little_endian() {
+        assert_eq!(
+            args(1, 100, 8).encode(),
+            "0x010029e560749fc5c785e3c1cfc3e14c0e608ec87120f0c066facf023ee8bd569d846400000008000000"
+        );
+        for (field, range) in DEX_ARGS_FIELDS {
+            assert!(range.end <= DEX_ARGS_LEN, "{field}");
+        }
+    }
+
+    #[test]
+    fn decode_errors() {
+        let encoded = args(0, 1, 0).encode();
+        assert_eq!(
+            DexArgs::decode("0xzz"),
+            Err(DexHelperError::ArgsDecodeError)
+        );
+        assert_eq!(
+            DexArgs::decode(&encoded[..encoded.len() - 2]),
+            Err(DexHelperError::ArgsLenError)
+        );
+        assert_eq!(
+            DexArgs::decode(&format!("{encoded}00")),
+            Err(DexHelperError::ArgsLenError)
+        );
+        let mut bytes = args(0, 1, 0).to_bytes();
+        bytes[0] = 3;
+        assert_eq!(DexArgs::from_bytes(&bytes), Err(DexHelperError::ModeTooBig));
+        assert_eq!(
+            parse_owner_script_hash("0x1234"),
+            Err(DexHelperError::LockScriptHashError)
+        );
+        assert_eq!(
+            parse_owner_script_hash("not hex"),
+            Err(DexHelperError::LockScriptHashError)
+        );
+    }
+
+    #[test]
+    fn prices() {
+        // UDT mode prices 10^8 raw units
+        let udt = args(0, 5, 7);
+        assert_eq!(udt.unit_price(), Some(50_000_000));
+        assert_eq!(udt.total_price(300_000_000), Some(150_000_000));
+        assert_eq!(udt.checked_total_price(300_000_000), Ok(150_000_000));
+        // other modes sell the whole cell
+        let cell = args(1, 100, 8);
+        assert_eq!(cell.total_price(12345), Some(10_000_000_000));
+        // 10^39 doesn't fit in a u128
+        assert_eq!(args(1, 1, 39).unit_price(), None);
+        assert_eq!(
+            args(0, u32::MAX, 29).checked_total_price(u128::MAX),
+            Err(DexHelperError::PriceOverflow)
+        );
+    }
+
+    #[test]
+    fn error_codes_are_stable() {
+        let errors = [
+            DexHelperError::ArgsDecodeError,
+            DexHelperError::LockScriptHashError,
+            DexHelperError::ModeTooBig,
+            DexHelperError::ArgsLenError,
+            DexHelperError::PriceOverflow,
+        ];
+        let codes: Vec<_> = errors.iter().map(|e| e.code()).collect();
+        assert_eq!(codes, [1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn only_sourced_exit_codes_are_explained() {
+        assert!(lock_error_message(1).is_some());
+        assert!(lock_error_message(4).is_some());
+        assert_eq!(lock_error_message(5), None);
+        assert_eq!(lock_error_message(-1), None);
+    }
+
+    #[test]
+    fn serde_uses_0x_hashes() {
+        let args = args(2, 1, 0);
+        let json = serde_json::to_value(&args).unwrap();
+        assert_eq!(json["owner_script_hash"], OWNER);
+        assert_eq!(serde_json::from_value::<DexArgs>(json).unwrap(), args);
+    }
+}
@@ -0,0 +1,378 @@
+//! Explanations of why some pasted text doesn't decode as dex args.
+
+use std::ops::Range;
+
+use crate::dex::{DexArgs, DEX_ARGS_LEN, MAX_MODE};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Hint,
+    Warning,
+    Error,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    /// Char offsets into the input the message is about.
+    pub span: Option<Range<usize>>,
+    /// [`normalize_args`] fixes it.
+    pub fixable: bool,
+}
+
+impl Diagnostic {
+    fn new(severity: Severity, message: impl Into<String>) -> Self {
+        Self {
+            severity,
+            message: message.into(),
+            span: None,
+            fixable: false,
+        }
+    }
+
+    fn span(mut self, span: Range<usize>) -> Self {
+        self.span = Some(span);
+        self
+    }
+
+    fn fixable(mut self) -> Self {
+        self.fixable = true;
+        self
+    }
+}
+
+const QUOTES: [char; 3] = ['"', '\'', '`'];
+
+fn strip_quotes(s: &str) -> Option<&str> {
+    QUOTES
+        .iter()
+        .find_map(|&quote| s.strip_prefix(quote).and_then(|s| s.strip_suffix(quote)))
+}
+
+/// Remove what commonly sneaks in when copying args: surrounding whitespace
+/// and quotes, an uppercase `0X` and whitespace between the digits.
+pub fn normalize_args(input: &str) -> String {
+    let s = input.trim();
+    let s = strip_quotes(s).unwrap_or(s).trim();
+    let s = s.strip_prefix("0X").unwrap_or(s);
+    let s = s.strip_prefix("0x").unwrap_or(s);
+    let digits: String = s.chars().filter(|c| !c.is_whitespace()).collect();
+    format!("0x{digits}")
+}
+
+/// Everything wrong (or suspicious) with `input` as dex args, worst first.
+///
+/// Empty when it decodes into plausible args.
+pub fn diagnose_args(input: &str) -> Vec<Diagnostic> {
+    let mut diagnostics = diagnose(input);
+    diagnostics.sort_by_key(|d| std::cmp::Reverse(d.severity));
+    diagnostics
+}
+
+fn diagnose(input: &str) -> Vec<Diagnostic> {
+    let mut diagnostics = vec![];
+    let chars: Vec<char> = input.chars().collect();
+    let char_count = chars.len();
+    if input.trim().is_empty() {
+        diagnostics.push(Diagnostic::new(Severity::Error, "Args are empty"));
+        return diagnostics;
+    }
+
+    // `start..end` is the char range still to look at.
+    let mut start = chars.iter().take_while(|c| c.is_whitespace()).count();
+    let mut end = char_count - chars.iter().rev().take_while(|c| c.is_whitespace()).count();
+    if start > 0 || end < char_count {
+        diagnostics
+            .push(Diagnostic::new(Severity::Warning, "Leading or trailing whitespace").fixable());
+    }
+    if end - start >= 2 && QUOTES.contains(&chars[start]) && chars[end - 1] == chars[start] {
+        diagnostics.push(
+            Diagnostic::new(Severity::Warning, "The args are wrapped in quotes")
+                .span(start..start + 1)
+                .fixable(),
+        );
+        start += 1;
+        end -= 1;
+        while start < end && chars[start].is_whitespace() {
+            start += 1;
+        }
+    }
+    if chars[start..end].starts_with(&['0', 'X']) {
+        diagnostics.push(
+            Diagnostic::new(Severity::Error, "The prefix must be a lowercase 0x, not 0X")
+                .span(start..start + 2)
+                .fixable(),
+        );
+        start += 2;
+    } else if chars[start..end].starts_with(&['0', 'x']) {
+        start += 2;
+    }
+
+    let mut digits = 0;
+    let mut whitespace = None;
+    for (offset, &c) in chars.iter().enumerate().take(end).skip(start) {
+        if c.is_whitespace() {
+            whitespace.get_or_insert(offset);
+        } else if c.is_ascii_hexdigit() {
+            digits += 1;
+        } else {
+            diagnostics.push(
+                Diagnostic::new(
+                    Severity::Error,
+                    format!(
+                        "{c:?} at char {offset} isn't a hex digit, it would be in byte {}",
+                        digits / 2
+                    ),
+                )
+                .span(offset..offset + 1),
+            );
+            return diagnostics;
+        }
+    }
+    if let Some(offset) = whitespace {
+        diagnostics.push(
+            Diagnostic::new(
+                Severity::Warning,
+                format!("Whitespace between the hex digits, first at char {offset}"),
+            )
+            .span(offset..offset + 1)
+            .fixable(),
+        );
+    }
+
+    if digits % 2 == 1 {
+        diagnostics.push(
+            Diagnostic::new(
+                Severity::Error,
+                format!("{digits} hex digits is an odd count, a digit is missing or extra"),
+            )
+            .span(start..end),
+        );
+        return diagnostics;
+    }
+    let bytes = digits / 2;
+    if bytes != DEX_ARGS_LEN {
+        diagnostics.push(
+            Diagnostic::new(
+                Severity::Error,
+                format!(
+                    "Args must be {DEX_ARGS_LEN} bytes ({} hex digits), got {bytes} bytes ({digits} hex digits, {char_count} chars in total)",
+                    DEX_ARGS_LEN * 2
+                ),
+            )
+            .span(start..end),
+        );
+        let hint = match bytes {
+            32 => Some(
+                "This looks like a 32-byte hash (e.g. an owner lock hash), not args".to_owned(),
+            ),
+            20 => Some(
+                "This looks like secp256k1 lock args (a 20-byte blake160), not dex args".to_owned(),
+            ),
+            0 => None,
+            n if n > DEX_ARGS_LEN => Some(format!(
+                "{} bytes too many, was something pasted twice or appended?",
+                n - DEX_ARGS_LEN
+            )),
+            n => Some(format!(
+                "{} bytes missing, was the copy cut off?",
+                DEX_ARGS_LEN - n
+            )),
+        };
+        diagnostics.extend(hint.map(|hint| Diagnostic::new(Severity::Hint, hint)));
+        return diagnostics;
+    }
+
+    let bytes = hex::decode(&normalize_args(input)[2..]).expect("only hex digits are left");
+    let args = DexArgs::from_bytes(&bytes).ok();
+    let mode = u16::from_le_bytes([bytes[0], bytes[1]]);
+    if mode > MAX_MODE {
+        diagnostics.push(Diagnostic::new(
+            Severity::Error,
+            format!(
+                "Mode is {mode} (bytes 0..2 are 0x{}), it must be 0, 1 or 2",
+                hex::encode(&bytes[..2])
+            ),
+        ));
+        let swapped = u16::from_be_bytes([bytes[0], bytes[1]]);
+        if swapped <= MAX_MODE {
+            diagnostics.push(Diagnostic::new(
+                Severity::Hint,
+                format!(
+                    "The mode looks big endian, mode {swapped} is 0x{}",
+                    hex::encode(swapped.to_le_bytes())
+                ),
+            ));
+        }
+    }
+    if let Some(args) = args {
+        if args.owner_script_hash == [0; 32] {
+            diagnostics.push(Diagnostic::new(
+                Severity::Warning,
+                "The owner lock hash is all zeros, nobody can cancel this order or get paid",
+            ));
+        }
+        if args.price_base == 0 {
+            diagnostics.push(Diagnostic::new(
+                Severity::Warning,
+                "The price is 0, anyone can take this order for free",
+            ));
+        }
+    }
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ARGS: &str =
+        "0x010029e560749fc5c785e3c1cfc3e14c0e608ec87120f0c066facf023ee8bd569d846400000008000000";
+
+    fn only(input: &str) -> Diagnostic {
+        let mut diagnostics = diagnose_args(input);
+        assert_eq!(diagnostics.len(), 1, "{diagnostics:?}");
+        diagnostics.remove(0)
+    }
+
+    #[test]
+    fn valid_args() {
+        assert_eq!(diagnose_args(ARGS), vec![]);
+        assert_eq!(diagnose_args(&ARGS[2..]), vec![]);
+        assert_eq!(normalize_args(ARGS), ARGS);
+    }
+
+    #[test]
+    fn empty() {
+        for input in ["", "  \n"] {
+            let diagnostic = only(input);
+            assert_eq!(diagnostic.severity, Severity::Error);
+            assert_eq!(diagnostic.message, "Args are empty");
+        }
+    }
+
+    #[test]
+    fn fixable_noise() {
+        let input = format!(" {ARGS}\n");
+        assert_eq!(
+            only(&input),
+            Diagnostic {
+                severity: Severity::Warning,
+                message: "Leading or trailing whitespace".to_owned(),
+                span: None,
+                fixable: true,
+            }
+        );
+        assert_eq!(normalize_args(&input), ARGS);
+
+        for quote in ['"', '\'', '`'] {
+            let input = format!("{quote}{ARGS}{quote}");
+            let diagnostic = only(&input);
+            assert_eq!(diagnostic.message, "The args are wrapped in quotes");
+            assert_eq!(diagnostic.span, Some(0..1));
+            assert!(diagnostic.fixable);
+            assert_eq!(normalize_args(&input), ARGS);
+        }
+
+        let input = format!("0X{}", &ARGS[2..]);
+        let diagnostic = only(&input);
+        assert_eq!(diagnostic.severity, Severity::Error);
+        assert_eq!(diagnostic.span, Some(0..2));
+        assert!(diagnostic.fixable);
+        assert_eq!(normalize_args(&input), ARGS);
+
+        let input = format!("{} {}", &ARGS[..10], &ARGS[10..]);
+        let diagnostic = only(&input);
+        assert_eq!(
+            diagnostic.message,
+            "Whitespace between the hex digits, first at char 10"
+        );
+        assert_eq!(diagnostic.span, Some(10..11));
+        assert_eq!(normalize_args(&input), ARGS);
+    }
+
+    #[test]
+    fn offsets_count_chars_past_quotes_and_whitespace() {
+        // the 'g' is char 8: space, quote, 0x and four digits before it
+        let input = format!(" \"0x0100g{}\"", &ARGS[7..]);
+        let diagnostics = diagnose_args(&input);
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+        assert_eq!(
+            diagnostics[0].message,
+            "'g' at char 8 isn't a hex digit, it would be in byte 2"
+        );
+        assert_eq!(diagnostics[0].span, Some(8..9));
+        assert!(!diagnostics[0].fixable);
+        assert_eq!(diagnostics[1].message, "Leading or trailing whitespace");
+        assert_eq!(diagnostics[2].span, Some(1..2));
+    }
+
+    #[test]
+    fn odd_length() {
+        let diagnostic = only(&ARGS[..85]);
+        assert_eq!(diagnostic.severity, Severity::Error);
+        assert_eq!(
+            diagnostic.message,
+            "83 hex digits is an odd count, a digit is missing or extra"
+        );
+        assert_eq!(diagnostic.span, Some(2..85));
+    }
+
+    #[test]
+    fn length_hints() {
+        let hint = |input: &str| {
+            let diagnostics = diagnose_args(input);
+            assert_eq!(diagnostics.len(), 2, "{diagnostics:?}");
+            assert_eq!(diagnostics[0].severity, Severity::Error);
+            assert_eq!(diagnostics[0].span, Some(2..input.len()));
+            assert_eq!(diagnostics[1].severity, Severity::Hint);
+            diagnostics[1].message.clone()
+        };
+        assert_eq!(
+            hint(&format!("0x{}", "ab".repeat(32))),
+            "This looks like a 32-byte hash (e.g. an owner lock hash), not args"
+        );
+        assert_eq!(
+            hint(&format!("0x{}", "11".repeat(20))),
+            "This looks like secp256k1 lock args (a 20-byte blake160), not dex args"
+        );
+        assert_eq!(hint(&ARGS[..80]), "3 bytes missing, was the copy cut off?");
+        assert_eq!(
+            hint(&format!("{ARGS}00")),
+            "1 bytes too many, was something pasted twice or appended?"
+        );
+        // a bare prefix is just too short
+        let diagnostic = only("0x");
+        assert_eq!(diagnostic.severity, Severity::Error);
+        assert_eq!(diagnostic.span, Some(2..2));
+    }
+
+    #[test]
+    fn big_endian_mode() {
+        let diagnostics = diagnose_args(&format!("0x0001{}", &ARGS[6..]));
+        assert_eq!(
+            diagnostics[0].message,
+            "Mode is 256 (bytes 0..2 are 0x0001), it must be 0, 1 or 2"
+        );
+        assert_eq!(diagnostics[1].severity, Severity::Hint);
+        assert_eq!(
+            diagnostics[1].message,
+            "The mode looks big endian, mode 1 is 0x0100"
+        );
+    }
+
+    #[test]
+    fn suspicious_values() {
+        let zeros = format!("0x0100{}0000000008000000", "00".repeat(32));
+        let diagnostics = diagnose_args(&zeros);
+        assert_eq!(diagnostics.len(), 2, "{diagnostics:?}");
+        assert!(diagnostics
+            .iter()
+            .all(|d| d.severity == Severity::Warning && !d.fixable));
+        assert!(diagnostics[0]
+            .message
+            .starts_with("The owner lock hash is all zeros"));
+        assert!(diagnostics[1].message.starts_with("The price is 0"));
+    }
+}
@@ -0,0 +1,360 @@
+//! C ABI of the codec, declared in `include/dex_helper.h`.
+//!
+//! Every function returns a [`DexHelperStatus`] and writes its result through
+//! an out pointer, which is left untouched on failure.
+
+use std::ffi::{c_char, CStr};
+
+use crate::dex::{self, DexArgs, DEX_ARGS_LEN, MAX_MODE};
+use crate::rpc::Script;
+use crate::tx::dex_lock_script;
+
+/// `0x` + 2 hex digits per byte + the terminating NUL.
+pub const DEX_HELPER_ARGS_HEX_LEN: usize = 2 + DEX_ARGS_LEN * 2 + 1;
+
+/// `0` on success, `1..=5` are the `DexHelperError` codes (the CLI exit codes).
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DexHelperStatus {
+    Ok = 0,
+    ArgsDecodeError = 1,
+    LockScriptHashError = 2,
+    ModeTooBig = 3,
+    ArgsLenError = 4,
+    PriceOverflow = 5,
+    /// A NULL pointer, a string that isn't UTF-8 or an unknown hash type.
+    InvalidArgument = 6,
+}
+
+impl From<dex::DexHelperError> for DexHelperStatus {
+    fn from(e: dex::DexHelperError) -> Self {
+        match e {
+            dex::DexHelperError::ArgsDecodeError => Self::ArgsDecodeError,
+            dex::DexHelperError::LockScriptHashError => Self::LockScriptHashError,
+            dex::DexHelperError::ModeTooBig => Self::ModeTooBig,
+            dex::DexHelperError::ArgsLenError => Self::ArgsLenError,
+            dex::DexHelperError::PriceOverflow => Self::PriceOverflow,
+        }
+    }
+}
+
+/// Decoded dex lock args.
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct DexHelperArgs {
+    pub mode: u16,
+    pub owner_script_hash: [u8; 32],
+    pub price_base: u32,
+    pub price_pow: u32,
+}
+
+impl From<&DexArgs> for DexHelperArgs {
+    fn from(args: &DexArgs) -> Self {
+        Self {
+            mode: args.mode,
+            owner_script_hash: args.owner_script_hash,
+            price_base: args.price_base,
+            price_pow: args.price_pow,
+        }
+    }
+}
+
+impl DexHelperArgs {
+    fn to_args(self) -> Result<DexArgs, DexHelperStatus> {
+        if self.mode > MAX_MODE {
+            return Err(DexHelperStatus::ModeTooBig);
+        }
+        Ok(DexArgs {
+            mode: self.mode,
+            owner_script_hash: self.owner_script_hash,
+            price_base: self.price_base,
+            price_pow: self.price_pow,
+        })
+    }
+}
+
+/// A `u128` as two little endian halves, C has no portable 128 bit integer.
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct DexHelperU128 {
+    pub lo: u64,
+    pub hi: u64,
+}
+
+impl From<DexHelperU128> for u128 {
+    fn from(n: DexHelperU128) -> Self {
+        (n.hi as u128) << 64 | n.lo as u128
+    }
+}
+
+impl From<u128> for DexHelperU128 {
+    fn from(n: u128) -> Self {
+        Self {
+            lo: n as u64,
+            hi: (n >> 64) as u64,
+        }
+    }
+}
+
+impl DexHelperStatus {
+    /// A status C passed back as a plain integer, `None` when it's no status.
+    fn from_raw(status: u32) -> Option<Self> {
+        Some(match status {
+            0 => Self::Ok,
+            1 => Self::ArgsDecodeError,
+            2 => Self::LockScriptHashError,
+            3 => Self::ModeTooBig,
+            4 => Self::ArgsLenError,
+            5 => Self::PriceOverflow,
+            6 => Self::InvalidArgument,
+            _ => return None,
+        })
+    }
+}
+
+fn status(result: Result<(), DexHelperStatus>) -> DexHelperStatus {
+    result.err().unwrap_or(DexHelperStatus::Ok)
+}
+
+/// # Safety
+///
+/// `args` must be NULL or point to a valid `DexHelperArgs`.
+unsafe fn read_args(args: *const DexHelperArgs) -> Result<DexArgs, DexHelperStatus> {
+    args.as_ref()
+        .ok_or(DexHelperStatus::InvalidArgument)?
+        .to_args()
+}
+
+/// # Safety
+///
+/// `out` must be NULL or valid for writes.
+unsafe fn write<T>(out: *mut T, value: T) -> Result<(), DexHelperStatus> {
+    if out.is_null() {
+        return Err(DexHelperStatus::InvalidArgument);
+    }
+    out.write(value);
+    Ok(())
+}
+
+/// # Safety
+///
+/// `out` must be NULL or valid for `bytes.len()` bytes of writes.
+unsafe fn write_bytes(out: *mut u8, bytes: &[u8]) -> Result<(), DexHelperStatus> {
+    if out.is_null() {
+        return Err(DexHelperStatus::InvalidArgument);
+    }
+    std::ptr::copy_nonoverlapping(bytes.as_ptr(), out, bytes.len());
+    Ok(())
+}
+
+/// Static description of a status, never NULL.
+///
+/// Takes the status as an integer, any value C may pass is safe and values
+/// that are no `DexHelperStatus` get "Unknown status".
+#[no_mangle]
+pub extern "C" fn dex_helper_status_message(status: u32) -> *const c_char {
+    let message = match DexHelperStatus::from_raw(status) {
+        Some(DexHelperStatus::Ok) => "Ok\0",
+        Some(DexHelperStatus::ArgsDecodeError) => "Args are not valid hex\0",
+        Some(DexHelperStatus::LockScriptHashError) => "LockScript Hash must be 32 bytes of hex\0",
+        Some(DexHelperStatus::ModeTooBig) => "Mode must be 0, 1 or 2\0",
+        Some(DexHelperStatus::ArgsLenError) => "Args must be 42 bytes\0",
+        Some(DexHelperStatus::PriceOverflow) => "Total price overflows\0",
+        Some(DexHelperStatus::InvalidArgument) => "Invalid argument\0",
+        None => "Unknown status\0",
+    };
+    message.as_ptr().cast()
+}
+
+/// Encode `args` into the 42 raw bytes of the lock args.
+///
+/// # Safety
+///
+/// `args` must point to a `DexHelperArgs` and `out` to `DEX_HELPER_ARGS_LEN` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn dex_helper_encode_args(
+    args: *const DexHelperArgs,
+    out: *mut u8,
+) -> DexHelperStatus {
+    status(read_args(args).and_then(|args| write_bytes(out, &args.to_bytes())))
+}
+
+/// Encode `args` as a NUL terminated `0x` prefixed hex string.
+///
+/// # Safety
+///
+/// `args` must point to a `DexHelperArgs` and `out` to
+/// `DEX_HELPER_ARGS_HEX_LEN` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn dex_helper_encode_args_hex(
+    args: *const DexHelperArgs,
+    out: *mut c_char,
+) -> DexHelperStatus {
+    status(read_args(args).and_then(|args| {
+        let hex = format!("{}\0", args.encode());
+        write_bytes(out.cast(), hex.as_bytes())
+    }))
+}
+
+/// Decode `len` raw bytes of lock args.
+///
+/// # Safety
+///
+/// `bytes` must point to `len` readable bytes and `out` to a writable `DexHelperArgs`.
+#[no_mangle]
+pub unsafe extern "C" fn dex_helper_decode_args(
+    bytes: *const u8,
+    len: usize,
+    out: *mut DexHelperArgs,
+) -> DexHelperStatus {
+    if bytes.is_null() {
+        return DexHelperStatus::InvalidArgument;
+    }
+    let bytes = std::slice::from_raw_parts(bytes, len);
+    status(
+        DexArgs::from_bytes(bytes)
+            .map_err(DexHelperStatus::from)
+            .and_then(|args| write(out, DexHelperArgs::from(&args))),
+    )
+}
+
+/// Decode lock args from a NUL terminated hex string, the `0x` prefix is optional.
+///
+/// # Safety
+///
+/// `hex` must be a NUL terminated string and `out` point to a writable `DexHelperArgs`.
+#[no_mangle]
+pub unsafe extern "C" fn dex_helper_decode_args_hex(
+    hex: *const c_char,
+    out: *mut DexHelperArgs,
+) -> DexHelperStatus {
+    if hex.is_null() {
+        return DexHelperStatus::InvalidArgument;
+    }
+    let Ok(hex) = CStr::from_ptr(hex).to_str() else {
+        return DexHelperStatus::InvalidArgument;
+    };
+    status(
+        DexArgs::decode(hex.trim())
+            .map_err(DexHelperStatus::from)
+            .and_then(|args| write(out, DexHelperArgs::from(&args))),
+    )
+}
+
+/// Exact total payment in shannons, `amount` only matters in UDT mode (0).
+///
+/// # Safety
+///
+/// `args` must point to a `DexHelperArgs` and `out` to a writable `DexHelperU128`.
+#[no_mangle]
+pub unsafe extern "C" fn dex_helper_total_price(
+    args: *const DexHelperArgs,
+    amount: DexHelperU128,
+    out: *mut DexHelperU128,
+) -> DexHelperStatus {
+    status(read_args(args).and_then(|args| {
+        let shannons = args.checked_total_price(amount.into())?;
+        write(out, shannons.into())
+    }))
+}
+
+/// Script hash of a script, e.g. the owner lock hash.
+///
+/// `hash_type` is the molecule byte: 0 data, 1 type, 2 data1, 4 data2.
+///
+/// # Safety
+///
+/// `code_hash` must point to 32 readable bytes, `args` to `args_len` readable
+/// bytes and `out` to 32 writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn dex_helper_script_hash(
+    code_hash: *const u8,
+    hash_type: u8,
+    args: *const u8,
+    args_len: usize,
+    out: *mut u8,
+) -> DexHelperStatus {
+    if code_hash.is_null() {
+        return DexHelperStatus::InvalidArgument;
+    }
+    let code_hash = std::slice::from_raw_parts(code_hash, 32);
+    if args.is_null() && args_len != 0 {
+        return DexHelperStatus::InvalidArgument;
+    }
+    let args = match args_len {
+        0 => &[][..],
+        _ => std::slice::from_raw_parts(args, args_len),
+    };
+    let hash_type = match hash_type {
+        0 => "data",
+        1 => "type",
+        2 => "data1",
+        4 => "data2",
+        _ => return DexHelperStatus::InvalidArgument,
+    };
+    let script = Script {
+        code_hash: format!("0x{}", hex::encode(code_hash)),
+        hash_type: hash_type.to_owned(),
+        args: format!("0x{}", hex::encode(args)),
+    };
+    status(
+        script
+            .calc_script_hash()
+            .map_err(|_| DexHelperStatus::InvalidArgument)
+            .and_then(|hash| write_bytes(out, &hash)),
+    )
+}
+
+/// Script hash of the dex lock with these args.
+///
+/// # Safety
+///
+/// `args` must point to a `DexHelperArgs` and `out` to 32 writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn dex_helper_dex_lock_hash(
+    args: *const DexHelperArgs,
+    out: *mut u8,
+) -> DexHelperStatus {
+    status(read_args(args).and_then(|args| {
+        let hash = dex_lock_script(&args)
+            .calc_script_hash()
+            .expect("the dex lock script is well formed");
+        write_bytes(out, &hash)
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(status: u32) -> &'static str {
+        unsafe { CStr::from_ptr(dex_helper_status_message(status)) }
+            .to_str()
+            .unwrap()
+    }
+
+    #[test]
+    fn committed_header_is_up_to_date() {
+        let generated = include_str!(concat!(env!("OUT_DIR"), "/dex_helper.h"));
+        let committed = include_str!("../include/dex_helper.h");
+        assert!(
+            generated == committed,
+            "include/dex_helper.h is out of date, copy {}/dex_helper.h over it",
+            env!("OUT_DIR")
+        );
+    }
+
+    #[test]
+    fn status_messages() {
+        assert_eq!(message(DexHelperStatus::Ok as u32), "Ok");
+        assert_eq!(
+            message(DexHelperStatus::InvalidArgument as u32),
+            "Invalid argument"
+        );
+        for raw in 0..=6 {
+            assert_eq!(DexHelperStatus::from_raw(raw).unwrap() as u32, raw);
+        }
+        assert_eq!(message(7), "Unknown status");
+        assert_eq!(message(u32::MAX), "Unknown status");
+    }
+}
@@ -1,4 +1,23 @@
 #![warn(clippy::all, rust_2018_idioms)]
 
+pub mod address;
 mod app;
+pub mod dex;
+pub mod diagnose;
+#[cfg(all(feature = "ffi", not(target_arch = "wasm32")))]
+pub mod ffi;
+#[cfg(all(feature = "mock-node", not(target_arch = "wasm32")))]
+pub mod mock_node;
+pub mod paste;
+#[cfg(all(feature = "python", not(target_arch = "wasm32")))]
+mod python;
+pub mod qr;
+pub mod rpc;
+pub mod script;
+#[cfg(all(feature = "serve", not(target_arch = "wasm32")))]
+pub mod server;
+pub mod trade;
+pub mod tx;
+#[cfg(target_arch = "wasm32")]
+pub mod wasm_api;
 pub use app::TemplateApp;
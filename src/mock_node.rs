@@ -0,0 +1,491 @@
+//! A stand-in CKB node answering the RPCs this tool uses from a JSON chain snapshot.
+//!
+//! Transactions sent to it are checked against the dex lock rules and applied to
+//! the snapshot, so multi-step flows (make, take, cancel) can run without a network.
+
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+use serde_json::{json, Value};
+
+use crate::dex::{strip_0x, DexArgs};
+use crate::rpc::{
+    CellOutput, OutPoint, Pagination, Script, SearchKey, Transaction, TransactionView,
+};
+use crate::script::blake2b_256;
+use crate::trade::{classify, Fill};
+
+/// Every script run is accounted the same, there is no VM in the mock.
+const CYCLES_PER_INPUT: u64 = 1_000_000;
+
+/// Initial chain state, every transaction is committed in its own block in order.
+#[derive(Clone, Debug, Default, serde::Deserialize, serde::Serialize)]
+pub struct ChainSnapshot {
+    pub transactions: Vec<TransactionView>,
+}
+
+/// JSON-RPC error returned to the client, `code` mirrors the node's codes.
+struct MockError {
+    code: i64,
+    message: String,
+}
+
+impl MockError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+
+    fn invalid_params(e: impl std::fmt::Display) -> Self {
+        Self::new(-32602, format!("Invalid params: {e}"))
+    }
+}
+
+#[derive(Default)]
+struct MockChain {
+    transactions: Vec<TransactionView>,
+    spent: HashSet<(String, u32)>,
+}
+
+impl MockChain {
+    fn new(snapshot: ChainSnapshot) -> Self {
+        let mut chain = Self::default();
+        for tx in snapshot.transactions {
+            chain.apply(tx);
+        }
+        chain
+    }
+
+    fn apply(&mut self, tx: TransactionView) {
+        for input in &tx.inner.inputs {
+            if let Some(index) = input.previous_output.index() {
+                self.spent
+                    .insert((input.previous_output.tx_hash.clone(), index));
+            }
+        }
+        self.transactions.push(tx);
+    }
+
+    fn find_transaction(&self, tx_hash: &str) -> Option<&TransactionView> {
+        self.transactions.iter().find(|tx| tx.hash == tx_hash)
+    }
+
+    /// The output at `out_point` and its data, whether spent or not.
+    fn find_output(&self, out_point: &OutPoint) -> Option<(&CellOutput, &String)> {
+        let tx = self.find_transaction(&out_point.tx_hash)?;
+        let index = out_point.index()? as usize;
+        Some((
+            tx.inner.outputs.get(index)?,
+            tx.inner.outputs_data.get(index)?,
+        ))
+    }
+
+    fn is_spent(&self, out_point: &OutPoint) -> bool {
+        out_point
+            .index()
+            .is_some_and(|i| self.spent.contains(&(out_point.tx_hash.clone(), i)))
+    }
+
+    /// All live cells as `(block_number, out_point, output, data)`, oldest first.
+    fn live_cells(&self) -> impl Iterator<Item = (usize, OutPoint, &CellOutput, &String)> {
+        self.transactions
+            .iter()
+            .enumerate()
+            .flat_map(|(block, tx)| {
+                tx.inner
+                    .outputs
+                    .iter()
+                    .zip(&tx.inner.outputs_data)
+                    .enumerate()
+                    .map(move |(i, (output, data))| {
+                        (block, OutPoint::new(&tx.hash, i as u32), output, data)
+                    })
+            })
+            .filter(|(_, out_point, _, _)| !self.is_spent(out_point))
+    }
+
+    fn handle(&mut self, method: &str, params: Value) -> Result<Value, MockError> {
+        match method {
+            "get_live_cell" => {
+                let (out_point, with_data): (OutPoint, bool) =
+                    serde_json::from_value(params).map_err(MockError::invalid_params)?;
+                Ok(self.get_live_cell(&out_point, with_data))
+            }
+            "get_transaction" => {
+                let (tx_hash,): (String,) =
+                    serde_json::from_value(params).map_err(MockError::invalid_params)?;
+                Ok(self.get_transaction(&tx_hash))
+            }
+            "get_cells" => {
+                let (search_key, order, limit, after): (SearchKey, String, String, Option<String>) =
+                    serde_json::from_value(params).map_err(MockError::invalid_params)?;
+                self.get_cells(&search_key, &order, &limit, after.as_deref())
+            }
+            "get_transactions" => {
+                let (search_key, order, limit, after): (SearchKey, String, String, Option<String>) =
+                    serde_json::from_value(params).map_err(MockError::invalid_params)?;
+                self.get_transactions(&search_key, &order, &limit, after.as_deref())
+            }
+            "dry_run_transaction" => {
+                let (tx,): (Transaction,) =
+                    serde_json::from_value(params).map_err(MockError::invalid_params)?;
+                let cycles = self.verify(&tx)?;
+                Ok(json!({ "cycles": format!("{cycles:#x}") }))
+            }
+            "send_transaction" => {
+                let (tx, _outputs_validator): (Transaction, Option<String>) =
+                    serde_json::from_value(params).map_err(MockError::invalid_params)?;
+                self.verify(&tx)?;
+                let hash = mock_tx_hash(&tx);
+                if self.find_transaction(&hash).is_some() {
+                    return Err(MockError::new(-1107, "PoolRejectedDuplicatedTransaction"));
+                }
+                self.apply(TransactionView {
+                    inner: tx,
+                    hash: hash.clone(),
+                });
+                Ok(json!(hash))
+            }
+            _ => Err(MockError::new(
+                -32601,
+                format!("Method not found: {method}"),
+            )),
+        }
+    }
+
+    fn get_live_cell(&self, out_point: &OutPoint, with_data: bool) -> Value {
+        match self.find_output(out_point) {
+            None => json!({ "cell": null, "status": "unknown" }),
+            Some(_) if self.is_spent(out_point) => json!({ "cell": null, "status": "dead" }),
+            Some((output, data)) => {
+                let data = with_data.then(|| {
+                    let bytes = hex::decode(strip_0x(data)).unwrap_or_default();
+                    json!({
+                        "content": data,
+                        "hash": format!("0x{}", hex::encode(blake2b_256(&bytes))),
+                    })
+                });
+                json!({ "cell": { "output": output, "data": data }, "status": "live" })
+            }
+        }
+    }
+
+    fn get_transaction(&self, tx_hash: &str) -> Value {
+        match self.find_transaction(tx_hash) {
+            Some(tx) => json!({
+                "transaction": tx,
+                "tx_status": { "status": "committed", "block_hash": null },
+            }),
+            None => json!({
+                "transaction": null,
+                "tx_status": { "status": "unknown", "block_hash": null },
+            }),
+        }
+    }
+
+    fn get_cells(
+        &self,
+        search_key: &SearchKey,
+        order: &str,
+        limit: &str,
+        after: Option<&str>,
+    ) -> Result<Value, MockError> {
+        let mut cells: Vec<_> = self
+            .live_cells()
+            .filter(|(_, _, output, _)| matches(search_key, output))
+            .map(|(block, out_point, output, data)| {
+                json!({
+                    "output": output,
+                    "output_data": data,
+                    "out_point": out_point,
+                    "block_number": format!("{block:#x}"),
+                    "tx_index": "0x0",
+                })
+            })
+            .collect();
+        if order == "desc" {
+            cells.reverse();
+        }
+        paginate(cells, limit, after)
+    }
+
+    fn get_transactions(
+        &self,
+        search_key: &SearchKey,
+        order: &str,
+        limit: &str,
+        after: Option<&str>,
+    ) -> Result<Value, MockError> {
+        let record = |tx_hash: &str, block: usize, io_index: usize, io_type: &str| {
+            json!({
+                "tx_hash": tx_hash,
+                "block_number": format!("{block:#x}"),
+                "tx_index": "0x0",
+                "io_index": format!("{io_index:#x}"),
+                "io_type": io_type,
+            })
+        };
+        let mut records = vec![];
+        for (block, tx) in self.transactions.iter().enumerate() {
+            for (i, input) in tx.inner.inputs.iter().enumerate() {
+                if let Some((output, _)) = self.find_output(&input.previous_output) {
+                    if matches(search_key, output) {
+                        records.push(record(&tx.hash, block, i, "input"));
+                    }
+                }
+            }
+            for (i, output) in tx.inner.outputs.iter().enumerate() {
+                if matches(search_key, output) {
+                    records.push(record(&tx.hash, block, i, "output"));
+                }
+            }
+        }
+        if order == "desc" {
+            records.reverse();
+        }
+        paginate(records, limit, after)
+    }
+
+    /// Resolve inputs and run the dex lock rules, returns the "consumed" cycles.
+    fn verify(&self, tx: &Transaction) -> Result<u64, MockError> {
+        let mut inputs = vec![];
+        for input in &tx.inputs {
+            let out_point = &input.previous_output;
+            match self.find_output(out_point) {
+                None => {
+                    return Err(MockError::new(
+                        -301,
+                        format!("TransactionFailedToResolve: Unknown(OutPoint({out_point}))"),
+                    ))
+                }
+                // listing a cell twice spends it twice, a real node calls the second one dead
+                Some(_)
+                    if self.is_spent(out_point)
+                        || inputs.iter().any(|(seen, _, _)| seen == out_point) =>
+                {
+                    return Err(MockError::new(
+                        -301,
+                        format!("TransactionFailedToResolve: Dead(OutPoint({out_point}))"),
+                    ))
+                }
+                Some((output, data)) => inputs.push((out_point.clone(), output, data)),
+            }
+        }
+
+        let capacity = |outputs: &mut dyn Iterator<Item = &CellOutput>| {
+            outputs
+                .map(|o| o.capacity().unwrap_or_default() as u128)
+                .sum::<u128>()
+        };
+        let inputs_capacity = capacity(&mut inputs.iter().map(|(_, output, _)| *output));
+        let outputs_capacity = capacity(&mut tx.outputs.iter());
+        if outputs_capacity > inputs_capacity {
+            return Err(MockError::new(
+                -302,
+                format!("TransactionFailedToVerify: Verification failed Transaction(OutputsSumOverflow: {outputs_capacity} > {inputs_capacity})"),
+            ));
+        }
+
+        let input_locks: Vec<_> = inputs
+            .iter()
+            .map(|(_, output, _)| output.lock.clone())
+            .collect();
+        for (i, (out_point, output, data)) in inputs.iter().enumerate() {
+            if !output.lock.is_dex_lock() {
+                continue;
+            }
+            // codes from `dex::lock_error_message` where one fits: 3 args too short or
+            // long, 4 malformed args (mode too big). An unpaid owner has no code there,
+            // the mock's own 7 shows up unexplained like any contract specific code.
+            let script_error = |exit_code: i8| {
+                MockError::new(
+                    -302,
+                    format!("TransactionFailedToVerify: Verification failed Script(TransactionScriptError {{ source: Inputs[{i}].Lock, cause: ValidationFailure: see error code {exit_code} on page https://nervosnetwork.github.io/ckb-script-error-codes/ }})"),
+                )
+            };
+            let args = match DexArgs::decode(&output.lock.args) {
+                Ok(args) => args,
+                Err(crate::dex::DexHelperError::ModeTooBig) => return Err(script_error(4)),
+                Err(_) => return Err(script_error(3)),
+            };
+            let cell = crate::rpc::DexCell::new(
+                out_point.clone(),
+                "live".to_owned(),
+                (*output).clone(),
+                (*data).clone(),
+            );
+            // The owner unlocking their own offer is a cancel, anything else must pay.
+            let owner_signed = input_locks.iter().any(|lock| {
+                lock.calc_script_hash()
+                    .is_ok_and(|hash| hash == args.owner_script_hash)
+            });
+            if !owner_signed && classify(&cell, &args, tx, &input_locks) == Fill::Cancelled {
+                return Err(script_error(7));
+            }
+        }
+        Ok(CYCLES_PER_INPUT * tx.inputs.len().max(1) as u64)
+    }
+}
+
+fn matches(search_key: &SearchKey, output: &CellOutput) -> bool {
+    let script: Option<&Script> = match search_key.script_type.as_str() {
+        "lock" => Some(&output.lock),
+        _ => output.type_.as_ref(),
+    };
+    let Some(script) = script else {
+        return false;
+    };
+    if script.code_hash != search_key.script.code_hash
+        || script.hash_type != search_key.script.hash_type
+    {
+        return false;
+    }
+    let args = strip_0x(&script.args).to_lowercase();
+    let wanted = strip_0x(&search_key.script.args).to_lowercase();
+    match search_key.script_search_mode.as_str() {
+        "exact" => args == wanted,
+        "partial" => args.contains(&wanted),
+        _ => args.starts_with(&wanted),
+    }
+}
+
+/// Cursors are just the position in the result list.
+fn paginate(objects: Vec<Value>, limit: &str, after: Option<&str>) -> Result<Value, MockError> {
+    let limit = crate::rpc::parse_hex_u64(limit)
+        .ok_or_else(|| MockError::invalid_params("limit"))? as usize;
+    let start = match after {
+        Some(cursor) => crate::rpc::parse_hex_u64(cursor)
+            .ok_or_else(|| MockError::invalid_params("cursor"))? as usize,
+        None => 0,
+    };
+    let objects: Vec<_> = objects.into_iter().skip(start).take(limit).collect();
+    let last_cursor = format!("{:#x}", start + objects.len());
+    Ok(serde_json::to_value(Pagination {
+        objects,
+        last_cursor,
+    })
+    .expect("json values always serialize"))
+}
+
+/// The mock doesn't serialize transactions with molecule, so its hashes differ from a real node's.
+fn mock_tx_hash(tx: &Transaction) -> String {
+    let json = serde_json::to_vec(tx).expect("a transaction always serializes");
+    format!("0x{}", hex::encode(blake2b_256(&json)))
+}
+
+/// A mock node serving JSON-RPC on a random localhost port until dropped.
+pub struct MockNode {
+    url: String,
+    chain: Arc<Mutex<MockChain>>,
+    server: Arc<tiny_http::Server>,
+    thread: Option<std::thread::JoinHandle<()>>,
+}
+
+impl MockNode {
+    /// Serve on a random free port.
+    pub fn start(snapshot: ChainSnapshot) -> std::io::Result<Self> {
+        Self::bind("127.0.0.1:0", snapshot)
+    }
+
+    pub fn bind(addr: &str, snapshot: ChainSnapshot) -> std::io::Result<Self> {
+        let server = tiny_http::Server::http(addr).map_err(std::io::Error::other)?;
+        let port = server
+            .server_addr()
+            .to_ip()
+            .map(|addr| addr.port())
+            .unwrap_or_default();
+        let server = Arc::new(server);
+        let chain = Arc::new(Mutex::new(MockChain::new(snapshot)));
+
+        let thread = {
+            let server = server.clone();
+            let chain = chain.clone();
+            std::thread::spawn(move || {
+                for request in server.incoming_requests() {
+                    serve(&chain, request);
+                }
+            })
+        };
+        Ok(Self {
+            url: format!("http://127.0.0.1:{port}"),
+            chain,
+            server,
+            thread: Some(thread),
+        })
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// The current chain, including every transaction sent so far.
+    pub fn snapshot(&self) -> ChainSnapshot {
+        ChainSnapshot {
+            transactions: self.chain.lock().unwrap().transactions.clone(),
+        }
+    }
+}
+
+impl Drop for MockNode {
+    fn drop(&mut self) {
+        self.server.unblock();
+        if let Some(thread) = self.thread.take() {
+            thread.join().ok();
+        }
+    }
+}
+
+fn header(name: &str, value: &str) -> tiny_http::Header {
+    tiny_http::Header::from_bytes(name, value).expect("a static header is valid")
+}
+
+fn serve(chain: &Mutex<MockChain>, mut request: tiny_http::Request) {
+    let cors = header("Access-Control-Allow-Origin", "*");
+    // the browser's CORS preflight before posting JSON from the web app
+    if request.method() == &tiny_http::Method::Options {
+        let response = tiny_http::Response::empty(204)
+            .with_header(cors)
+            .with_header(header("Access-Control-Allow-Methods", "POST, OPTIONS"))
+            .with_header(header("Access-Control-Allow-Headers", "Content-Type"));
+        if let Err(e) = request.respond(response) {
+            log::warn!("mock node failed to respond: {e}");
+        }
+        return;
+    }
+    let mut body = String::new();
+    let response = match request.as_reader().read_to_string(&mut body) {
+        Err(e) => {
+            json!({ "jsonrpc": "2.0", "id": null, "error": { "code": -32700, "message": e.to_string() } })
+        }
+        Ok(_) => match serde_json::from_str::<Value>(&body) {
+            Err(e) => {
+                json!({ "jsonrpc": "2.0", "id": null, "error": { "code": -32700, "message": e.to_string() } })
+            }
+            Ok(call) => {
+                let method = call["method"].as_str().unwrap_or_default();
+                let params = call.get("params").cloned().unwrap_or(json!([]));
+                let result = chain.lock().unwrap().handle(method, params);
+                log::debug!(
+                    "mock node {method}: {}",
+                    if result.is_ok() { "ok" } else { "error" }
+                );
+                match result {
+                    Ok(result) => json!({ "jsonrpc": "2.0", "id": call["id"], "result": result }),
+                    Err(e) => json!({
+                        "jsonrpc": "2.0",
+                        "id": call["id"],
+                        "error": { "code": e.code, "message": e.message },
+                    }),
+                }
+            }
+        },
+    };
+    let response = tiny_http::Response::from_string(response.to_string())
+        .with_header(header("Content-Type", "application/json"))
+        .with_header(cors);
+    if let Err(e) = request.respond(response) {
+        log::warn!("mock node failed to respond: {e}");
+    }
+}
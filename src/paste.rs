@@ -0,0 +1,168 @@
+//! Finding dex lock args in whatever gets pasted into the args box.
+
+use crate::address::parse_address;
+use crate::dex::strip_0x;
+use crate::rpc::{format_ckb, CellOutput, Script, Transaction};
+use crate::tx::parse_transaction_json;
+
+/// Dex lock args found in pasted text.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Candidate {
+    /// Where in the paste they were, e.g. `output 2 (200 CKB)`.
+    pub label: String,
+    pub args: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Paste {
+    /// Nothing recognized, to be decoded as args as is.
+    Raw,
+    /// `kind` holds dex lock args, more than one candidate when it's a
+    /// transaction with several dex cells.
+    Found {
+        kind: &'static str,
+        candidates: Vec<Candidate>,
+    },
+    /// `kind` was recognized but has no dex lock.
+    NoDexLock { kind: &'static str },
+}
+
+fn from_script(kind: &'static str, script: Script) -> Paste {
+    if !script.is_dex_lock() {
+        return Paste::NoDexLock { kind };
+    }
+    Paste::Found {
+        kind,
+        candidates: vec![Candidate {
+            label: kind.to_owned(),
+            args: script.args,
+        }],
+    }
+}
+
+fn from_transaction(tx: Transaction) -> Paste {
+    let kind = "transaction";
+    let candidates: Vec<_> = tx
+        .outputs
+        .into_iter()
+        .enumerate()
+        .filter(|(_, output)| output.lock.is_dex_lock())
+        .map(|(i, output)| Candidate {
+            label: match output.capacity() {
+                Some(capacity) => format!("output {i} ({} CKB)", format_ckb(capacity)),
+                None => format!("output {i}"),
+            },
+            args: output.lock.args,
+        })
+        .collect();
+    if candidates.is_empty() {
+        // inputs only have out points, the dex cells they spend would have to be fetched
+        return Paste::NoDexLock { kind };
+    }
+    Paste::Found { kind, candidates }
+}
+
+fn from_json(json: &str) -> Option<Paste> {
+    let value: serde_json::Value = serde_json::from_str(json).ok()?;
+    // a whole JSON-RPC response, e.g. from `get_transaction`
+    let value = match value.get("result") {
+        Some(result) => result.clone(),
+        None => value,
+    };
+    if let Ok(script) = serde_json::from_value::<Script>(value.clone()) {
+        return Some(from_script("lock script", script));
+    }
+    if let Ok(output) = serde_json::from_value::<CellOutput>(value.clone()) {
+        return Some(from_script("cell output", output.lock));
+    }
+    let tx = parse_transaction_json(&value.to_string()).ok()?;
+    Some(from_transaction(tx))
+}
+
+/// Recognize a lock script (JSON or molecule), a cell output, an address or a
+/// transaction (JSON, bare, wrapped or as an RPC response) and pull the dex
+/// lock args out of it.
+pub fn recognize(input: &str) -> Paste {
+    let input = input.trim();
+    if input.starts_with('{') {
+        return from_json(input).unwrap_or(Paste::Raw);
+    }
+    if input.starts_with("ckb1") || input.starts_with("ckt1") {
+        return match parse_address(input) {
+            Ok((_, script)) => from_script("address", script),
+            Err(_) => Paste::Raw,
+        };
+    }
+    // dex args are 42 bytes, a molecule script with them is 95
+    if let Ok(bytes) = hex::decode(strip_0x(input)) {
+        if let Ok(script) = Script::from_molecule(&bytes) {
+            return from_script("molecule script", script);
+        }
+    }
+    Paste::Raw
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dex::DEX_LOCK_CODE_HASH;
+
+    const ARGS: &str =
+        "0x010029e560749fc5c785e3c1cfc3e14c0e608ec87120f0c066facf023ee8bd569d846400000008000000";
+
+    fn found(paste: Paste) -> (&'static str, Vec<String>) {
+        match paste {
+            Paste::Found { kind, candidates } => {
+                (kind, candidates.into_iter().map(|c| c.args).collect())
+            }
+            paste => panic!("{paste:?}"),
+        }
+    }
+
+    #[test]
+    fn script_json_in_both_spellings() {
+        let snake = format!(
+            r#"{{"code_hash": "{DEX_LOCK_CODE_HASH}", "hash_type": "type", "args": "{ARGS}"}}"#
+        );
+        let camel = format!(
+            r#"{{"codeHash": "{DEX_LOCK_CODE_HASH}", "hashType": "type", "args": "{ARGS}"}}"#
+        );
+        for json in [snake, camel] {
+            assert_eq!(
+                found(recognize(&json)),
+                ("lock script", vec![ARGS.to_owned()])
+            );
+        }
+    }
+
+    #[test]
+    fn other_hash_type_is_no_dex_lock() {
+        let json = format!(
+            r#"{{"codeHash": "{DEX_LOCK_CODE_HASH}", "hashType": "data", "args": "{ARGS}"}}"#
+        );
+        assert_eq!(
+            recognize(&json),
+            Paste::NoDexLock {
+                kind: "lock script"
+            }
+        );
+    }
+
+    #[test]
+    fn camel_case_transaction() {
+        let json = format!(
+            r#"{{"version": "0x0", "cellDeps": [], "headerDeps": [], "inputs": [],
+              "outputs": [{{"capacity": "0x4a817c800", "lock": {{"codeHash": "{DEX_LOCK_CODE_HASH}", "hashType": "type", "args": "{ARGS}"}}, "type": null}}],
+              "outputsData": ["0x"], "witnesses": []}}"#
+        );
+        assert_eq!(
+            found(recognize(&json)),
+            ("transaction", vec![ARGS.to_owned()])
+        );
+    }
+
+    #[test]
+    fn raw_args_stay_raw() {
+        assert_eq!(recognize(ARGS), Paste::Raw);
+    }
+}
@@ -0,0 +1,95 @@
+//! The `dex_helper` Python module, build it with `maturin develop --features python`.
+//!
+//! Failures raise `dex_helper.DexHelperError(message, code)`, `code` being
+//! [`crate::dex::DexHelperError::code`].
+
+use pyo3::create_exception;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+use crate::dex::{self, parse_owner_script_hash, DexArgs, MAX_MODE};
+use crate::rpc::Script;
+
+create_exception!(dex_helper, DexHelperError, PyValueError);
+
+fn to_py_err(e: dex::DexHelperError) -> PyErr {
+    DexHelperError::new_err((e.to_string(), e.code()))
+}
+
+fn decode(args: &str) -> PyResult<DexArgs> {
+    DexArgs::decode(args.trim()).map_err(to_py_err)
+}
+
+/// Encode dex lock args as `0x` prefixed hex.
+#[pyfunction]
+fn encode_args(
+    mode: u16,
+    owner_script_hash: &str,
+    price_base: u32,
+    price_pow: u32,
+) -> PyResult<String> {
+    if mode > MAX_MODE {
+        return Err(to_py_err(dex::DexHelperError::ModeTooBig));
+    }
+    let args = DexArgs {
+        mode,
+        owner_script_hash: parse_owner_script_hash(owner_script_hash.trim()).map_err(to_py_err)?,
+        price_base,
+        price_pow,
+    };
+    Ok(args.encode())
+}
+
+/// Decode dex lock args into a dict with the same keys as `encode_args` takes.
+#[pyfunction]
+fn decode_args<'py>(py: Python<'py>, args: &str) -> PyResult<Bound<'py, PyDict>> {
+    let args = decode(args)?;
+    let dict = PyDict::new_bound(py);
+    dict.set_item("mode", args.mode)?;
+    dict.set_item("owner_script_hash", args.owner_script_hash_hex())?;
+    dict.set_item("price_base", args.price_base)?;
+    dict.set_item("price_pow", args.price_pow)?;
+    Ok(dict)
+}
+
+/// Shannons per unit, `price_base * 10^price_pow`.
+#[pyfunction]
+fn unit_price(args: &str) -> PyResult<u128> {
+    decode(args)?
+        .unit_price()
+        .ok_or_else(|| to_py_err(dex::DexHelperError::PriceOverflow))
+}
+
+/// Exact total payment in shannons, `amount` only matters in UDT mode (0).
+#[pyfunction]
+#[pyo3(signature = (args, amount=0))]
+fn total_price(args: &str, amount: u128) -> PyResult<u128> {
+    decode(args)?.checked_total_price(amount).map_err(to_py_err)
+}
+
+/// Script hash (blake2b of the molecule `Script`), e.g. the owner lock hash.
+#[pyfunction]
+fn script_hash(code_hash: &str, hash_type: &str, args: &str) -> PyResult<String> {
+    let script = Script {
+        code_hash: code_hash.trim().to_owned(),
+        hash_type: hash_type.trim().to_owned(),
+        args: args.trim().to_owned(),
+    };
+    script
+        .calc_script_hash_hex()
+        .map_err(|e| PyValueError::new_err(format!("Invalid script: {e:?}")))
+}
+
+#[pymodule]
+fn dex_helper(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("DexHelperError", m.py().get_type_bound::<DexHelperError>())?;
+    m.add("DEX_LOCK_CODE_HASH", dex::DEX_LOCK_CODE_HASH)?;
+    m.add("DEX_LOCK_HASH_TYPE", dex::DEX_LOCK_HASH_TYPE)?;
+    m.add_function(wrap_pyfunction!(encode_args, m)?)?;
+    m.add_function(wrap_pyfunction!(decode_args, m)?)?;
+    m.add_function(wrap_pyfunction!(unit_price, m)?)?;
+    m.add_function(wrap_pyfunction!(total_price, m)?)?;
+    m.add_function(wrap_pyfunction!(script_hash, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,173 @@
+//! QR codes of args, addresses and transactions, for wallets to scan.
+
+use qrcodegen::{QrCode, QrCodeEcc};
+
+/// Most bytes put in one QR code. Larger texts are split into frames of this
+/// size, denser codes are hard to scan from a screen.
+pub(crate) const MAX_FRAME_LEN: usize = 600;
+
+/// Light modules around the code, the quiet zone the spec asks for.
+pub(crate) const BORDER: i32 = 4;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QrError {
+    /// Even a single frame doesn't fit in a QR code.
+    TooLong,
+    Png,
+}
+
+impl std::fmt::Display for QrError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::TooLong => write!(f, "Too much data for a QR code"),
+            Self::Png => write!(f, "PNG encoding failed"),
+        }
+    }
+}
+
+impl std::error::Error for QrError {}
+
+/// The texts to encode, one per QR code.
+///
+/// Text up to [`MAX_FRAME_LEN`] bytes is a single frame as is. Longer text is
+/// split into `"{index}/{count}:{part}"` frames, `index` counting from 1, to
+/// be shown one after the other and joined back by the scanner.
+pub fn frames(text: &str) -> Vec<String> {
+    if text.len() <= MAX_FRAME_LEN {
+        return vec![text.to_owned()];
+    }
+    let mut parts = vec![];
+    let mut rest = text;
+    while !rest.is_empty() {
+        let mut end = rest.len().min(MAX_FRAME_LEN);
+        while !rest.is_char_boundary(end) {
+            end -= 1;
+        }
+        parts.push(&rest[..end]);
+        rest = &rest[end..];
+    }
+    let count = parts.len();
+    parts
+        .iter()
+        .enumerate()
+        .map(|(i, part)| format!("{}/{count}:{part}", i + 1))
+        .collect()
+}
+
+pub fn encode(text: &str) -> Result<QrCode, QrError> {
+    QrCode::encode_text(text, QrCodeEcc::Medium).map_err(|_| QrError::TooLong)
+}
+
+/// Side of the image in modules, the border included.
+fn image_size(qr: &QrCode) -> i32 {
+    qr.size() + 2 * BORDER
+}
+
+/// `true` for a dark module, coordinates include the border.
+fn is_dark(qr: &QrCode, x: i32, y: i32) -> bool {
+    // out of range modules (the border) are light
+    qr.get_module(x - BORDER, y - BORDER)
+}
+
+/// An SVG image, one unit per module.
+pub fn to_svg(qr: &QrCode) -> String {
+    let size = image_size(qr);
+    let mut path = String::new();
+    for y in 0..size {
+        for x in 0..size {
+            if is_dark(qr, x, y) {
+                path.push_str(&format!("M{x},{y}h1v1h-1z"));
+            }
+        }
+    }
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 {size} {size}\" shape-rendering=\"crispEdges\">\
+<rect width=\"100%\" height=\"100%\" fill=\"#ffffff\"/>\
+<path d=\"{path}\" fill=\"#000000\"/></svg>\n"
+    )
+}
+
+/// A grayscale PNG image, `scale` pixels per module.
+pub fn to_png(qr: &QrCode, scale: u32) -> Result<Vec<u8>, QrError> {
+    let size = image_size(qr) as u32;
+    let side = size * scale;
+    let mut pixels = Vec::with_capacity((side * side) as usize);
+    for py in 0..side {
+        for px in 0..side {
+            let dark = is_dark(qr, (px / scale) as i32, (py / scale) as i32);
+            pixels.push(if dark { 0 } else { 255 });
+        }
+    }
+    let mut png = vec![];
+    let mut encoder = png::Encoder::new(&mut png, side, side);
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder
+        .write_header()
+        .and_then(|mut writer| writer.write_image_data(&pixels))
+        .map_err(|_| QrError::Png)?;
+    Ok(png)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// What a scanner does with the frames: strip the headers, join in order.
+    fn join(frames: &[String]) -> String {
+        let count = frames.len();
+        frames
+            .iter()
+            .enumerate()
+            .map(|(i, frame)| {
+                let part = frame
+                    .strip_prefix(&format!("{}/{count}:", i + 1))
+                    .expect("a frame header");
+                assert!(part.len() <= MAX_FRAME_LEN);
+                part
+            })
+            .collect()
+    }
+
+    #[test]
+    fn short_text_is_one_frame() {
+        assert_eq!(frames("0x0100"), vec!["0x0100".to_owned()]);
+        // no header even at the limit
+        let text = "a".repeat(MAX_FRAME_LEN);
+        assert_eq!(frames(&text), vec![text]);
+    }
+
+    #[test]
+    fn one_byte_over_is_two_frames() {
+        let text = format!("{}b", "a".repeat(MAX_FRAME_LEN));
+        let frames = frames(&text);
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0], format!("1/2:{}", "a".repeat(MAX_FRAME_LEN)));
+        assert_eq!(frames[1], "2/2:b");
+        assert_eq!(join(&frames), text);
+    }
+
+    #[test]
+    fn splits_on_char_boundaries() {
+        // the 3-byte '€' would straddle byte 600, it moves to the second frame
+        let text = format!("{}€{}", "a".repeat(MAX_FRAME_LEN - 1), "b".repeat(10));
+        let frames = frames(&text);
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0], format!("1/2:{}", "a".repeat(MAX_FRAME_LEN - 1)));
+        assert_eq!(frames[1], format!("2/2:€{}", "b".repeat(10)));
+        assert_eq!(join(&frames), text);
+    }
+
+    #[test]
+    fn long_text_joins_back() {
+        let text: String = (0..2000)
+            .map(|i| char::from(b'a' + (i % 26) as u8))
+            .collect();
+        let frames = frames(&text);
+        assert_eq!(frames.len(), 4);
+        assert!(frames[3].starts_with("4/4:"));
+        assert_eq!(join(&frames), text);
+        // every frame fits in a code
+        assert!(frames.iter().all(|frame| encode(frame).is_ok()));
+    }
+}
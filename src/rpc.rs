@@ -0,0 +1,547 @@
+use serde::de::DeserializeOwned;
+
+use crate::dex::{strip_0x, DexArgs, DexHelperError, DEX_LOCK_CODE_HASH, DEX_LOCK_HASH_TYPE};
+
+pub const DEFAULT_RPC_URL: &str = "https://mainnet.ckb.dev/rpc";
+
+/// JSON-RPC error code of an unknown method.
+const METHOD_NOT_FOUND: i64 = -32601;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RpcError {
+    /// The request never got a response (network, CORS, bad url...).
+    Transport(String),
+    /// The endpoint answered with a non 2xx HTTP status.
+    Status(u16, String),
+    /// The response is not the JSON we expected.
+    Json(String),
+    /// The node answered with a JSON-RPC error object.
+    Rpc { code: i64, message: String },
+    /// The node answered, but the thing we asked for does not exist.
+    NotFound(String),
+    /// The cell exists, but another lock than the dex lock guards it.
+    NotDexCell(String),
+}
+
+/// A script that failed verification, parsed from the node's error message.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScriptFailure {
+    /// e.g. `Inputs[0].Lock`
+    pub source: String,
+    pub exit_code: i8,
+}
+
+impl RpcError {
+    /// Find the failing script and its exit code in a `TransactionFailedToVerify` error.
+    pub fn script_failure(&self) -> Option<ScriptFailure> {
+        let Self::Rpc { message, .. } = self else {
+            return None;
+        };
+        let source = message
+            .split("source: ")
+            .nth(1)?
+            .split([',', ' ', ')'])
+            .next()?
+            .to_owned();
+        let exit_code = message
+            .split("error code ")
+            .nth(1)?
+            .split(|c: char| c != '-' && !c.is_ascii_digit())
+            .next()?
+            .parse()
+            .ok()?;
+        Some(ScriptFailure { source, exit_code })
+    }
+}
+
+impl std::fmt::Display for RpcError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Transport(e) => write!(f, "Request failed: {e}"),
+            Self::Status(status, text) => write!(f, "HTTP {status} {text}"),
+            Self::Json(e) => write!(f, "Invalid response: {e}"),
+            Self::Rpc { code, message } => write!(f, "RPC error {code}: {message}"),
+            Self::NotFound(what) => write!(f, "{what} not found"),
+            Self::NotDexCell(what) => {
+                write!(f, "{what} is not a dex cell, its lock isn't the dex lock")
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct Script {
+    /// Explorers and the JS SDKs spell it `codeHash`, accepted as well.
+    #[serde(alias = "codeHash")]
+    pub code_hash: String,
+    #[serde(alias = "hashType")]
+    pub hash_type: String,
+    pub args: String,
+}
+
+impl Script {
+    /// Whether this is the dex lock. The code hash alone isn't enough, with
+    /// another hash type it's other code.
+    pub fn is_dex_lock(&self) -> bool {
+        self.code_hash.to_lowercase() == DEX_LOCK_CODE_HASH && self.hash_type == DEX_LOCK_HASH_TYPE
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct OutPoint {
+    #[serde(alias = "txHash")]
+    pub tx_hash: String,
+    /// `Uint32` as a `0x` prefixed hex string.
+    pub index: String,
+}
+
+impl OutPoint {
+    pub fn new(tx_hash: &str, index: u32) -> Self {
+        Self {
+            tx_hash: format!("0x{}", strip_0x(tx_hash.trim())),
+            index: format!("{index:#x}"),
+        }
+    }
+
+    pub fn index(&self) -> Option<u32> {
+        u32::from_str_radix(strip_0x(&self.index), 16).ok()
+    }
+}
+
+impl std::fmt::Display for OutPoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.tx_hash, self.index().unwrap_or_default())
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct CellOutput {
+    /// Shannons, `Uint64` as a `0x` prefixed hex string.
+    pub capacity: String,
+    pub lock: Script,
+    #[serde(rename = "type")]
+    pub type_: Option<Script>,
+}
+
+impl CellOutput {
+    pub fn capacity(&self) -> Option<u64> {
+        parse_hex_u64(&self.capacity)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct CellData {
+    pub content: String,
+    pub hash: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct CellInfo {
+    pub output: CellOutput,
+    pub data: Option<CellData>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct CellWithStatus {
+    pub cell: Option<CellInfo>,
+    /// `live`, `dead` or `unknown`.
+    pub status: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct CellDep {
+    #[serde(alias = "outPoint")]
+    pub out_point: OutPoint,
+    #[serde(alias = "depType")]
+    pub dep_type: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct CellInput {
+    #[serde(alias = "previousOutput")]
+    pub previous_output: OutPoint,
+    pub since: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct Transaction {
+    pub version: String,
+    #[serde(alias = "cellDeps")]
+    pub cell_deps: Vec<CellDep>,
+    #[serde(alias = "headerDeps")]
+    pub header_deps: Vec<String>,
+    pub inputs: Vec<CellInput>,
+    pub outputs: Vec<CellOutput>,
+    #[serde(alias = "outputsData")]
+    pub outputs_data: Vec<String>,
+    pub witnesses: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct TransactionView {
+    #[serde(flatten)]
+    pub inner: Transaction,
+    pub hash: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct TxStatus {
+    /// `pending`, `proposed`, `committed`, `unknown` or `rejected`.
+    pub status: String,
+    pub block_hash: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct TransactionWithStatus {
+    pub transaction: Option<TransactionView>,
+    pub tx_status: TxStatus,
+}
+
+/// Indexer search key, see the `get_cells` RPC.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct SearchKey {
+    pub script: Script,
+    /// `lock` or `type`.
+    pub script_type: String,
+    /// `prefix`, `exact` or `partial`, matched against `script.args`.
+    pub script_search_mode: String,
+}
+
+impl SearchKey {
+    /// Search dex cells whose args start with `args_prefix`.
+    pub fn dex_lock(args_prefix: &str) -> Self {
+        Self::lock_prefix(DEX_LOCK_CODE_HASH, DEX_LOCK_HASH_TYPE, args_prefix)
+    }
+
+    /// Search cells whose lock args start with `args_prefix`.
+    pub fn lock_prefix(code_hash: &str, hash_type: &str, args_prefix: &str) -> Self {
+        Self {
+            script: Script {
+                code_hash: code_hash.to_owned(),
+                hash_type: hash_type.to_owned(),
+                args: args_prefix.to_owned(),
+            },
+            script_type: "lock".to_owned(),
+            script_search_mode: "prefix".to_owned(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct IndexerCell {
+    pub output: CellOutput,
+    pub output_data: Option<String>,
+    pub out_point: OutPoint,
+    pub block_number: String,
+    pub tx_index: String,
+}
+
+/// A transaction touching a searched script, see the `get_transactions` RPC.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct IndexerTx {
+    pub tx_hash: String,
+    pub block_number: String,
+    pub tx_index: String,
+    /// Index into the transaction's inputs or outputs, see `io_type`.
+    pub io_index: String,
+    /// `input` or `output`.
+    pub io_type: String,
+}
+
+impl IndexerTx {
+    pub fn io_index(&self) -> Option<usize> {
+        parse_hex_u64(&self.io_index).map(|i| i as usize)
+    }
+
+    pub fn block_number(&self) -> Option<u64> {
+        parse_hex_u64(&self.block_number)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct Pagination<T> {
+    pub objects: Vec<T>,
+    pub last_cursor: String,
+}
+
+#[derive(serde::Deserialize)]
+struct Cycles {
+    cycles: String,
+}
+
+#[derive(serde::Deserialize)]
+struct RpcErrorObject {
+    code: i64,
+    message: String,
+}
+
+#[derive(serde::Deserialize)]
+struct RpcResponse<T> {
+    result: Option<T>,
+    error: Option<RpcErrorObject>,
+}
+
+/// A JSON-RPC client of a CKB node, works both natively and on web.
+#[derive(Clone, Debug)]
+pub struct RpcClient {
+    url: String,
+}
+
+impl RpcClient {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self { url: url.into() }
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    fn request(&self, method: &str, params: serde_json::Value) -> ehttp::Request {
+        let body = serde_json::json!({
+            "id": 1,
+            "jsonrpc": "2.0",
+            "method": method,
+            "params": params,
+        });
+        ehttp::Request::json(&self.url, &body).expect("a json value always serializes")
+    }
+
+    fn parse_response<T: DeserializeOwned>(
+        response: ehttp::Result<ehttp::Response>,
+    ) -> Result<T, RpcError> {
+        let response = response.map_err(RpcError::Transport)?;
+        if !response.ok {
+            return Err(RpcError::Status(response.status, response.status_text));
+        }
+        let response: RpcResponse<T> =
+            response.json().map_err(|e| RpcError::Json(e.to_string()))?;
+        if let Some(error) = response.error {
+            return Err(RpcError::Rpc {
+                code: error.code,
+                message: error.message,
+            });
+        }
+        response
+            .result
+            .ok_or_else(|| RpcError::Json("missing result".to_owned()))
+    }
+
+    /// Call `method` and hand the result to `on_done`, which runs on a background thread.
+    pub fn call<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+        on_done: impl 'static + Send + FnOnce(Result<T, RpcError>),
+    ) {
+        ehttp::fetch(self.request(method, params), move |response| {
+            on_done(Self::parse_response(response));
+        });
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn call_blocking<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+    ) -> Result<T, RpcError> {
+        Self::parse_response(ehttp::fetch_blocking(&self.request(method, params)))
+    }
+
+    pub fn get_live_cell(
+        &self,
+        out_point: &OutPoint,
+        on_done: impl 'static + Send + FnOnce(Result<CellWithStatus, RpcError>),
+    ) {
+        self.call(
+            "get_live_cell",
+            serde_json::json!([out_point, true]),
+            on_done,
+        );
+    }
+
+    pub fn get_transaction(
+        &self,
+        tx_hash: &str,
+        on_done: impl 'static + Send + FnOnce(Result<TransactionWithStatus, RpcError>),
+    ) {
+        self.call("get_transaction", serde_json::json!([tx_hash]), on_done);
+    }
+
+    /// Page through live cells, pass the previous `last_cursor` as `after` to continue.
+    pub fn get_cells(
+        &self,
+        search_key: &SearchKey,
+        limit: u32,
+        after: Option<&str>,
+        on_done: impl 'static + Send + FnOnce(Result<Pagination<IndexerCell>, RpcError>),
+    ) {
+        let params = serde_json::json!([search_key, "asc", format!("{limit:#x}"), after]);
+        self.call("get_cells", params, on_done);
+    }
+
+    /// Page through transactions touching `search_key`, newest first.
+    pub fn get_transactions(
+        &self,
+        search_key: &SearchKey,
+        limit: u32,
+        after: Option<&str>,
+        on_done: impl 'static + Send + FnOnce(Result<Pagination<IndexerTx>, RpcError>),
+    ) {
+        let params = serde_json::json!([search_key, "desc", format!("{limit:#x}"), after]);
+        self.call("get_transactions", params, on_done);
+    }
+
+    /// Run all scripts of `tx` without sending it, returns the consumed cycles.
+    ///
+    /// Uses `estimate_cycles` and falls back to the older `dry_run_transaction`
+    /// on nodes that don't have it.
+    pub fn estimate_cycles(
+        &self,
+        tx: &Transaction,
+        on_done: impl 'static + Send + FnOnce(Result<u64, RpcError>),
+    ) {
+        let client = self.clone();
+        let tx = tx.clone();
+        self.call("estimate_cycles", serde_json::json!([&tx]), move |result| {
+            let parse = |result: Result<Cycles, RpcError>| {
+                result.and_then(|c| parse_hex_u64(&c.cycles).ok_or(RpcError::Json(c.cycles)))
+            };
+            match result {
+                Err(RpcError::Rpc {
+                    code: METHOD_NOT_FOUND,
+                    ..
+                }) => {
+                    client.call(
+                        "dry_run_transaction",
+                        serde_json::json!([tx]),
+                        move |result| on_done(parse(result)),
+                    );
+                }
+                result => on_done(parse(result)),
+            }
+        });
+    }
+
+    /// Broadcast a signed transaction, returns its hash.
+    pub fn send_transaction(
+        &self,
+        tx: &Transaction,
+        on_done: impl 'static + Send + FnOnce(Result<String, RpcError>),
+    ) {
+        self.call(
+            "send_transaction",
+            serde_json::json!([tx, "passthrough"]),
+            on_done,
+        );
+    }
+
+    /// Fetch the cell at `out_point` and decode its dex lock args.
+    ///
+    /// Live cells come from `get_live_cell`, cells that were already consumed
+    /// are looked up in their creating transaction instead. Cells with another
+    /// lock are a [`RpcError::NotDexCell`], even when their args are 42 bytes.
+    pub fn fetch_dex_cell(
+        &self,
+        out_point: OutPoint,
+        on_done: impl 'static + Send + FnOnce(Result<DexCell, RpcError>),
+    ) {
+        let on_done = move |result: Result<DexCell, RpcError>| {
+            on_done(result.and_then(|cell| {
+                if cell.output.lock.is_dex_lock() {
+                    Ok(cell)
+                } else {
+                    Err(RpcError::NotDexCell(format!("Cell {}", cell.out_point)))
+                }
+            }))
+        };
+        let client = self.clone();
+        self.get_live_cell(&out_point.clone(), move |result| match result {
+            Err(e) => on_done(Err(e)),
+            Ok(CellWithStatus {
+                cell: Some(cell),
+                status,
+            }) => {
+                let data = cell.data.map(|d| d.content).unwrap_or_default();
+                on_done(Ok(DexCell::new(out_point, status, cell.output, data)));
+            }
+            Ok(CellWithStatus { status, .. }) => {
+                client.get_transaction(&out_point.tx_hash.clone(), move |result| {
+                    on_done(result.and_then(|tx| DexCell::from_transaction(out_point, status, tx)))
+                });
+            }
+        });
+    }
+}
+
+/// A cell locked (or claimed to be locked) by the dex lock.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DexCell {
+    pub out_point: OutPoint,
+    /// Status reported by `get_live_cell`.
+    pub status: String,
+    pub output: CellOutput,
+    /// Hex encoded output data.
+    pub data: String,
+    pub args: Result<DexArgs, DexHelperError>,
+}
+
+impl DexCell {
+    pub fn new(out_point: OutPoint, status: String, output: CellOutput, data: String) -> Self {
+        let args = DexArgs::decode(&output.lock.args);
+        Self {
+            out_point,
+            status,
+            output,
+            data,
+            args,
+        }
+    }
+
+    pub fn from_indexer(cell: IndexerCell) -> Self {
+        let data = cell.output_data.unwrap_or_default();
+        Self::new(cell.out_point, "live".to_owned(), cell.output, data)
+    }
+
+    fn from_transaction(
+        out_point: OutPoint,
+        status: String,
+        tx: TransactionWithStatus,
+    ) -> Result<Self, RpcError> {
+        let not_found = || RpcError::NotFound(format!("Cell {out_point}"));
+        let tx = tx.transaction.ok_or_else(not_found)?;
+        let index = out_point.index().ok_or_else(not_found)? as usize;
+        let output = tx.inner.outputs.get(index).cloned().ok_or_else(not_found)?;
+        let data = tx
+            .inner
+            .outputs_data
+            .get(index)
+            .cloned()
+            .unwrap_or_default();
+        Ok(Self::new(out_point, status, output, data))
+    }
+
+    pub fn is_live(&self) -> bool {
+        self.status == "live"
+    }
+
+    pub fn data_bytes(&self) -> Vec<u8> {
+        hex::decode(strip_0x(&self.data)).unwrap_or_default()
+    }
+}
+
+pub fn parse_hex_u64(s: &str) -> Option<u64> {
+    u64::from_str_radix(strip_0x(s), 16).ok()
+}
+
+/// Format shannons as CKB, keeping all 8 decimals that are not zero.
+pub fn format_ckb(shannons: impl Into<u128>) -> String {
+    let shannons = shannons.into();
+    let whole = shannons / 100_000_000;
+    let frac = shannons % 100_000_000;
+    if frac == 0 {
+        whole.to_string()
+    } else {
+        let frac = format!("{frac:08}");
+        format!("{whole}.{}", frac.trim_end_matches('0'))
+    }
+}
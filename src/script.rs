@@ -0,0 +1,102 @@
+use crate::dex::strip_0x;
+use crate::rpc::Script;
+
+/// Personalization of the blake2b hash used everywhere on CKB.
+const CKB_HASH_PERSONALIZATION: &[u8] = b"ckb-default-hash";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScriptError {
+    CodeHashError,
+    HashTypeError,
+    ArgsError,
+}
+
+pub fn blake2b_256(data: &[u8]) -> [u8; 32] {
+    let hash = blake2b_simd::Params::new()
+        .hash_length(32)
+        .personal(CKB_HASH_PERSONALIZATION)
+        .hash(data);
+    let mut result = [0u8; 32];
+    result.copy_from_slice(hash.as_bytes());
+    result
+}
+
+pub fn hash_type_to_byte(hash_type: &str) -> Result<u8, ScriptError> {
+    match hash_type {
+        "data" => Ok(0),
+        "type" => Ok(1),
+        "data1" => Ok(2),
+        "data2" => Ok(4),
+        _ => Err(ScriptError::HashTypeError),
+    }
+}
+
+pub fn hash_type_from_byte(hash_type: u8) -> Result<&'static str, ScriptError> {
+    match hash_type {
+        0 => Ok("data"),
+        1 => Ok("type"),
+        2 => Ok("data1"),
+        4 => Ok("data2"),
+        _ => Err(ScriptError::HashTypeError),
+    }
+}
+
+impl Script {
+    /// Parse a molecule serialized `Script` table, the inverse of [`Self::to_molecule`].
+    pub fn from_molecule(bytes: &[u8]) -> Result<Self, ScriptError> {
+        let u32_at = |i: usize| -> Option<usize> {
+            Some(u32::from_le_bytes(bytes.get(i..i + 4)?.try_into().ok()?) as usize)
+        };
+        let header_len = 4 * 4;
+        let args_offset = header_len + 32 + 1;
+        if bytes.len() < args_offset + 4
+            || u32_at(0) != Some(bytes.len())
+            || u32_at(4) != Some(header_len)
+            || u32_at(8) != Some(header_len + 32)
+            || u32_at(12) != Some(args_offset)
+        {
+            return Err(ScriptError::CodeHashError);
+        }
+        if u32_at(args_offset) != Some(bytes.len() - args_offset - 4) {
+            return Err(ScriptError::ArgsError);
+        }
+        Ok(Self {
+            code_hash: format!("0x{}", hex::encode(&bytes[header_len..header_len + 32])),
+            hash_type: hash_type_from_byte(bytes[header_len + 32])?.to_owned(),
+            args: format!("0x{}", hex::encode(&bytes[args_offset + 4..])),
+        })
+    }
+
+    /// Molecule serialization of the `Script` table.
+    pub fn to_molecule(&self) -> Result<Vec<u8>, ScriptError> {
+        let code_hash =
+            hex::decode(strip_0x(&self.code_hash)).map_err(|_| ScriptError::CodeHashError)?;
+        if code_hash.len() != 32 {
+            return Err(ScriptError::CodeHashError);
+        }
+        let hash_type = hash_type_to_byte(&self.hash_type)?;
+        let args = hex::decode(strip_0x(&self.args)).map_err(|_| ScriptError::ArgsError)?;
+
+        // header: total size + 3 field offsets, then code_hash(32) || hash_type(1) || args(bytes)
+        let header_len = 4 * 4;
+        let args_offset = header_len + 32 + 1;
+        let total_len = args_offset + 4 + args.len();
+        let mut bytes = Vec::with_capacity(total_len);
+        for n in [total_len, header_len, header_len + 32, args_offset] {
+            bytes.extend_from_slice(&(n as u32).to_le_bytes());
+        }
+        bytes.extend_from_slice(&code_hash);
+        bytes.push(hash_type);
+        bytes.extend_from_slice(&(args.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&args);
+        Ok(bytes)
+    }
+
+    pub fn calc_script_hash(&self) -> Result<[u8; 32], ScriptError> {
+        Ok(blake2b_256(&self.to_molecule()?))
+    }
+
+    pub fn calc_script_hash_hex(&self) -> Result<String, ScriptError> {
+        Ok(format!("0x{}", hex::encode(self.calc_script_hash()?)))
+    }
+}
@@ -0,0 +1,404 @@
+//! A local HTTP API over the dex args codec, for services that don't link Rust.
+//!
+//! Every endpoint takes and returns JSON with `POST`:
+//!
+//! - `/encode` `{mode, owner_script_hash, price_base, price_pow}`
+//! - `/decode` `{args}`
+//! - `/price` `{amount, args}` or `{amount, mode, price_base, price_pow}`,
+//!   the price is returned in shannons as a decimal string
+//! - `/tx/make` `{args, capacity, type?, data?}`, `capacity` in shannons
+//! - `/tx/cancel` `{out_point, output, data, owner_lock}`
+//!
+//! Invalid requests get a `400` with `{"error", "code"}`, `code` being
+//! [`DexHelperError::code`] or `null` when the body isn't valid JSON for the endpoint.
+
+use std::sync::Arc;
+
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+
+use crate::dex::{parse_owner_script_hash, DexArgs, DexHelperError, MAX_MODE};
+use crate::rpc::{format_ckb, CellOutput, DexCell, OutPoint, Script};
+use crate::tx::{cancel_offer, make_offer};
+
+#[derive(Debug)]
+pub enum ApiError {
+    Dex(DexHelperError),
+    BadRequest(String),
+    NotFound,
+    MethodNotAllowed,
+}
+
+impl From<DexHelperError> for ApiError {
+    fn from(e: DexHelperError) -> Self {
+        Self::Dex(e)
+    }
+}
+
+impl ApiError {
+    pub fn status(&self) -> u16 {
+        match self {
+            Self::Dex(_) | Self::BadRequest(_) => 400,
+            Self::NotFound => 404,
+            Self::MethodNotAllowed => 405,
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        let code = match self {
+            Self::Dex(e) => Some(e.code()),
+            _ => None,
+        };
+        json!({ "error": self.to_string(), "code": code })
+    }
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Dex(e) => write!(f, "{e}"),
+            Self::BadRequest(e) => write!(f, "{e}"),
+            Self::NotFound => write!(f, "Unknown endpoint"),
+            Self::MethodNotAllowed => write!(f, "Use POST"),
+        }
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct OrderRequest {
+    mode: u16,
+    owner_script_hash: String,
+    price_base: u32,
+    price_pow: u32,
+}
+
+impl OrderRequest {
+    fn to_args(&self) -> Result<DexArgs, DexHelperError> {
+        if self.mode > MAX_MODE {
+            return Err(DexHelperError::ModeTooBig);
+        }
+        Ok(DexArgs {
+            mode: self.mode,
+            owner_script_hash: parse_owner_script_hash(&self.owner_script_hash)?,
+            price_base: self.price_base,
+            price_pow: self.price_pow,
+        })
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct ArgsRequest {
+    args: String,
+}
+
+/// `u128` amounts don't fit in a JSON number, so a decimal string is accepted too.
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum Amount {
+    Number(u64),
+    String(String),
+}
+
+impl Amount {
+    fn value(&self) -> Result<u128, ApiError> {
+        match self {
+            Self::Number(n) => Ok(*n as u128),
+            Self::String(s) => s
+                .trim()
+                .parse()
+                .map_err(|_| ApiError::BadRequest(format!("invalid amount {s}"))),
+        }
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct PriceRequest {
+    amount: Amount,
+    args: Option<String>,
+    mode: Option<u16>,
+    price_base: Option<u32>,
+    price_pow: Option<u32>,
+}
+
+#[derive(serde::Deserialize)]
+struct MakeRequest {
+    args: String,
+    capacity: u64,
+    #[serde(rename = "type")]
+    type_: Option<Script>,
+    data: Option<String>,
+}
+
+#[derive(serde::Deserialize)]
+struct CancelRequest {
+    out_point: OutPoint,
+    output: CellOutput,
+    data: String,
+    owner_lock: Script,
+}
+
+fn parse<T: DeserializeOwned>(body: &str) -> Result<T, ApiError> {
+    serde_json::from_str(body).map_err(|e| ApiError::BadRequest(format!("Invalid request: {e}")))
+}
+
+fn args_json(args: &DexArgs) -> Value {
+    let mut value = serde_json::to_value(args).expect("args always serialize");
+    value["args"] = args.encode().into();
+    value
+}
+
+/// Answer one request, returns the JSON response body or the error to report.
+pub fn handle(method: &str, path: &str, body: &str) -> Result<Value, ApiError> {
+    let path = path
+        .split('?')
+        .next()
+        .unwrap_or_default()
+        .trim_end_matches('/');
+    if !matches!(
+        path,
+        "/encode" | "/decode" | "/price" | "/tx/make" | "/tx/cancel"
+    ) {
+        return Err(ApiError::NotFound);
+    }
+    if method != "POST" {
+        return Err(ApiError::MethodNotAllowed);
+    }
+    match path {
+        "/encode" => Ok(args_json(&parse::<OrderRequest>(body)?.to_args()?)),
+        "/decode" => Ok(args_json(&DexArgs::decode(
+            parse::<ArgsRequest>(body)?.args.trim(),
+        )?)),
+        "/price" => {
+            let request: PriceRequest = parse(body)?;
+            let args = match (&request.args, request.mode) {
+                (Some(args), _) => DexArgs::decode(args.trim())?,
+                (None, Some(mode)) if mode > MAX_MODE => {
+                    return Err(DexHelperError::ModeTooBig.into())
+                }
+                (None, Some(mode)) => {
+                    let missing = |name: &str| ApiError::BadRequest(format!("{name} is required"));
+                    DexArgs {
+                        mode,
+                        owner_script_hash: [0; 32],
+                        price_base: request.price_base.ok_or_else(|| missing("price_base"))?,
+                        price_pow: request.price_pow.ok_or_else(|| missing("price_pow"))?,
+                    }
+                }
+                (None, None) => {
+                    return Err(ApiError::BadRequest("args or mode is required".to_owned()))
+                }
+            };
+            let shannons = args.checked_total_price(request.amount.value()?)?;
+            Ok(json!({ "shannons": shannons.to_string(), "ckb": format_ckb(shannons) }))
+        }
+        "/tx/make" => {
+            let request: MakeRequest = parse(body)?;
+            let args = DexArgs::decode(request.args.trim())?;
+            let tx = make_offer(
+                &args,
+                request.capacity,
+                request.type_,
+                request.data.unwrap_or_else(|| "0x".to_owned()),
+            );
+            Ok(serde_json::to_value(tx).expect("a transaction always serializes"))
+        }
+        "/tx/cancel" => {
+            let request: CancelRequest = parse(body)?;
+            if !request.output.lock.is_dex_lock() {
+                return Err(ApiError::BadRequest(
+                    "The cell isn't locked by the dex lock".to_owned(),
+                ));
+            }
+            let cell = DexCell::new(
+                request.out_point,
+                "live".to_owned(),
+                request.output,
+                request.data,
+            );
+            if let Err(e) = cell.args {
+                return Err(e.into());
+            }
+            let tx = cancel_offer(&cell, &request.owner_lock);
+            Ok(serde_json::to_value(tx).expect("a transaction always serializes"))
+        }
+        _ => unreachable!("checked above"),
+    }
+}
+
+/// The API served on a background thread until dropped.
+pub struct ApiServer {
+    url: String,
+    server: Arc<tiny_http::Server>,
+    thread: Option<std::thread::JoinHandle<()>>,
+}
+
+impl ApiServer {
+    pub fn bind(addr: &str) -> std::io::Result<Self> {
+        let server = tiny_http::Server::http(addr).map_err(std::io::Error::other)?;
+        let url = match server.server_addr().to_ip() {
+            Some(addr) => format!("http://{addr}"),
+            None => format!("http://{addr}"),
+        };
+        let server = Arc::new(server);
+
+        let thread = {
+            let server = server.clone();
+            std::thread::spawn(move || {
+                for request in server.incoming_requests() {
+                    serve(request);
+                }
+            })
+        };
+        Ok(Self {
+            url,
+            server,
+            thread: Some(thread),
+        })
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+impl Drop for ApiServer {
+    fn drop(&mut self) {
+        self.server.unblock();
+        if let Some(thread) = self.thread.take() {
+            thread.join().ok();
+        }
+    }
+}
+
+fn serve(mut request: tiny_http::Request) {
+    let mut body = String::new();
+    let result = match request.as_reader().read_to_string(&mut body) {
+        Err(e) => Err(ApiError::BadRequest(e.to_string())),
+        Ok(_) => handle(request.method().as_str(), request.url(), &body),
+    };
+    log::debug!(
+        "{} {}: {}",
+        request.method(),
+        request.url(),
+        match &result {
+            Ok(_) => "ok".to_owned(),
+            Err(e) => e.to_string(),
+        }
+    );
+    let (status, body) = match result {
+        Ok(value) => (200, value),
+        Err(e) => (e.status(), e.to_json()),
+    };
+    let header = tiny_http::Header::from_bytes("Content-Type", "application/json")
+        .expect("a static header is valid");
+    let response = tiny_http::Response::from_string(body.to_string())
+        .with_status_code(status)
+        .with_header(header);
+    if let Err(e) = request.respond(response) {
+        log::warn!("api server failed to respond: {e}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dex::{DEX_LOCK_CODE_HASH, DEX_LOCK_HASH_TYPE};
+
+    const OWNER: &str = "0x29e560749fc5c785e3c1cfc3e14c0e608ec87120f0c066facf023ee8bd569d84";
+    const ARGS: &str =
+        "0x010029e560749fc5c785e3c1cfc3e14c0e608ec87120f0c066facf023ee8bd569d846400000008000000";
+
+    fn post(path: &str, body: Value) -> Result<Value, ApiError> {
+        handle("POST", path, &body.to_string())
+    }
+
+    fn cancel_request(hash_type: &str) -> Value {
+        json!({
+            "out_point": { "tx_hash": format!("0x{}", "ab".repeat(32)), "index": "0x0" },
+            "output": {
+                "capacity": "0x4a817c800",
+                "lock": { "code_hash": DEX_LOCK_CODE_HASH, "hash_type": hash_type, "args": ARGS },
+                "type": null,
+            },
+            "data": "0x",
+            "owner_lock": {
+                "code_hash": "0x9bd7e06f3ecf4be0f2fcd2188b23f1b9fcc88e5d4b65a8637b17723bbda3cce8",
+                "hash_type": "type",
+                "args": format!("0x{}", "11".repeat(20)),
+            },
+        })
+    }
+
+    #[test]
+    fn encode_decode() {
+        let encoded = post(
+            "/encode",
+            json!({ "mode": 1, "owner_script_hash": OWNER, "price_base": 100, "price_pow": 8 }),
+        )
+        .unwrap();
+        assert_eq!(encoded["args"], ARGS);
+        assert_eq!(encoded["price_base"], 100);
+
+        let decoded = post("/decode", json!({ "args": format!(" {ARGS} ") })).unwrap();
+        assert_eq!(decoded, encoded);
+    }
+
+    #[test]
+    fn price() {
+        // mode 1 pays 100 * 10^8 shannons whatever the amount
+        let price = post("/price", json!({ "amount": 5, "args": ARGS })).unwrap();
+        assert_eq!(price, json!({ "shannons": "10000000000", "ckb": "100" }));
+        // mode 0 pays per 10^8 of amount, given as a string past u64
+        let price = post(
+            "/price",
+            json!({ "amount": "300000000", "mode": 0, "price_base": 2, "price_pow": 0 }),
+        )
+        .unwrap();
+        assert_eq!(price["shannons"], "6");
+    }
+
+    #[test]
+    fn error_bodies() {
+        let error = post("/decode", json!({ "args": "0x0100" })).unwrap_err();
+        assert_eq!(error.status(), 400);
+        assert_eq!(error.to_json()["code"], 4);
+
+        let error = post(
+            "/price",
+            json!({ "amount": 1, "mode": 3, "price_base": 1, "price_pow": 0 }),
+        )
+        .unwrap_err();
+        assert_eq!(error.to_json()["code"], 3);
+
+        let error = post("/price", json!({ "amount": 1, "mode": 0 })).unwrap_err();
+        assert_eq!(error.status(), 400);
+        assert_eq!(
+            error.to_json(),
+            json!({ "error": "price_base is required", "code": null })
+        );
+
+        let error = handle("POST", "/encode", "not json").unwrap_err();
+        assert_eq!(error.status(), 400);
+        assert_eq!(error.to_json()["code"], Value::Null);
+    }
+
+    #[test]
+    fn unknown_paths_and_methods() {
+        assert_eq!(handle("POST", "/nope", "{}").unwrap_err().status(), 404);
+        assert_eq!(handle("GET", "/encode", "").unwrap_err().status(), 405);
+        // a trailing slash or a query string is the same endpoint
+        assert!(handle("POST", "/decode/?x=1", &json!({ "args": ARGS }).to_string()).is_ok());
+    }
+
+    #[test]
+    fn cancel_checks_the_whole_dex_lock() {
+        let tx = post("/tx/cancel", cancel_request(DEX_LOCK_HASH_TYPE)).unwrap();
+        assert_eq!(tx["inputs"][0]["previous_output"]["index"], "0x0");
+        assert_eq!(tx["outputs"][0]["capacity"], "0x4a817c800");
+
+        // the dex lock's code hash with another hash type is other code
+        let error = post("/tx/cancel", cancel_request("data")).unwrap_err();
+        assert_eq!(error.status(), 400);
+        assert_eq!(error.to_string(), "The cell isn't locked by the dex lock");
+    }
+}
@@ -0,0 +1,342 @@
+use std::collections::VecDeque;
+
+use crate::dex::{udt_amount, DexArgs};
+use crate::rpc::{
+    DexCell, IndexerTx, OutPoint, RpcClient, RpcError, Script, SearchKey, Transaction,
+};
+
+/// Indexer records fetched per `get_transactions` call.
+const PAGE_SIZE: u32 = 100;
+
+/// How a dex cell left the order book.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum Fill {
+    /// Someone paid the owner, `paid` is the shannons the owner received on top of the cell capacity.
+    Taken { paid: u128 },
+    /// No payment to the owner, so the owner must have unlocked it.
+    Cancelled,
+}
+
+/// Tell whether `tx` consuming `cell` took or cancelled the offer.
+///
+/// `input_locks` are the locks of the cells `tx` spends. When one of them is
+/// the owner's lock the owner unlocked the offer themselves, a cancel however
+/// much they pay themselves, e.g. merging it with their other cells. Otherwise
+/// the offer is taken when an output locked by the owner carries at least the
+/// cell capacity plus the total price, which is what the dex lock checks.
+pub fn classify(cell: &DexCell, args: &DexArgs, tx: &Transaction, input_locks: &[Script]) -> Fill {
+    let is_owner = |lock: &Script| {
+        lock.calc_script_hash()
+            .is_ok_and(|hash| hash == args.owner_script_hash)
+    };
+    if input_locks.iter().any(is_owner) {
+        return Fill::Cancelled;
+    }
+    let capacity = cell.output.capacity().unwrap_or_default() as u128;
+    let amount = udt_amount(&cell.data_bytes()).unwrap_or_default();
+    let price = args.total_price(amount).unwrap_or(u128::MAX);
+    tx.outputs
+        .iter()
+        .filter(|output| is_owner(&output.lock))
+        .filter_map(|output| output.capacity())
+        .map(|received| received as u128)
+        .filter(|&received| received >= capacity.saturating_add(price))
+        .max()
+        .map_or(Fill::Cancelled, |received| Fill::Taken {
+            paid: received - capacity,
+        })
+}
+
+/// A dex cell consumed by a transaction.
+#[derive(Clone, Debug)]
+pub struct Trade {
+    pub cell: DexCell,
+    pub args: DexArgs,
+    /// UDT amount read from cell data, only meaningful in mode 0.
+    pub amount: Option<u128>,
+    pub tx_hash: String,
+    pub block_number: u64,
+    pub fill: Fill,
+}
+
+/// Resolve an indexer `input` record of the dex lock into a [`Trade`].
+///
+/// `on_done` gets `Ok(None)` when the consumed cell's args are not valid dex args.
+pub fn fetch_trade(
+    client: &RpcClient,
+    record: IndexerTx,
+    on_done: impl 'static + Send + FnOnce(Result<Option<Trade>, RpcError>),
+) {
+    let fetch_client = client.clone();
+    let tx_hash = record.tx_hash.clone();
+    client.get_transaction(&tx_hash, move |result| {
+        let not_found = || RpcError::NotFound(format!("Transaction {}", record.tx_hash));
+        let tx = match result.and_then(|tx| tx.transaction.ok_or_else(not_found)) {
+            Ok(tx) => tx.inner,
+            Err(e) => return on_done(Err(e)),
+        };
+        let Some(input) = record.io_index().and_then(|i| tx.inputs.get(i)) else {
+            return on_done(Err(not_found()));
+        };
+        let inputs = tx
+            .inputs
+            .iter()
+            .map(|input| input.previous_output.clone())
+            .collect();
+        fetch_client
+            .clone()
+            .fetch_dex_cell(input.previous_output.clone(), move |result| {
+                let cell = match result {
+                    Ok(cell) => cell,
+                    Err(e) => return on_done(Err(e)),
+                };
+                let Ok(args) = cell.args.clone() else {
+                    return on_done(Ok(None));
+                };
+                fetch_input_locks(fetch_client, inputs, Vec::new(), move |result| {
+                    on_done(result.map(|input_locks| {
+                        Some(Trade {
+                            fill: classify(&cell, &args, &tx, &input_locks),
+                            amount: udt_amount(&cell.data_bytes()),
+                            args,
+                            cell,
+                            tx_hash: record.tx_hash.clone(),
+                            block_number: record.block_number().unwrap_or_default(),
+                        })
+                    }))
+                });
+            });
+    });
+}
+
+/// Look up the locks of the cells at `out_points` one by one, live or not.
+fn fetch_input_locks(
+    client: RpcClient,
+    mut out_points: VecDeque<OutPoint>,
+    mut locks: Vec<Script>,
+    on_done: impl 'static + Send + FnOnce(Result<Vec<Script>, RpcError>),
+) {
+    let Some(out_point) = out_points.pop_front() else {
+        return on_done(Ok(locks));
+    };
+    client
+        .clone()
+        .get_transaction(&out_point.tx_hash.clone(), move |result| {
+            let not_found = || RpcError::NotFound(format!("Cell {out_point}"));
+            let lock = result
+                .and_then(|tx| tx.transaction.ok_or_else(not_found))
+                .and_then(|tx| {
+                    let index = out_point.index().ok_or_else(not_found)?;
+                    tx.inner
+                        .outputs
+                        .into_iter()
+                        .nth(index as usize)
+                        .map(|output| output.lock)
+                        .ok_or_else(not_found)
+                });
+            match lock {
+                Ok(lock) => {
+                    locks.push(lock);
+                    fetch_input_locks(client, out_points, locks, on_done);
+                }
+                Err(e) => on_done(Err(e)),
+            }
+        });
+}
+
+/// Whether a dex cell is still on offer, and how it left the order book if not.
+#[derive(Clone, Debug)]
+pub enum OfferStatus {
+    Live,
+    /// `None` when the consuming transaction can't be found through the indexer.
+    Consumed(Option<Box<Trade>>),
+    /// The node never saw this cell.
+    Unknown,
+}
+
+/// Check `out_point` (locked with `lock_args`) and find the transaction consuming it if it's dead.
+pub fn fetch_offer_status(
+    client: &RpcClient,
+    out_point: OutPoint,
+    lock_args: &str,
+    on_done: impl 'static + Send + FnOnce(Result<OfferStatus, RpcError>),
+) {
+    let client = client.clone();
+    let lock_args = lock_args.to_owned();
+    client
+        .clone()
+        .get_live_cell(&out_point.clone(), move |result| {
+            match result.map(|cell| cell.status) {
+                Err(e) => on_done(Err(e)),
+                Ok(status) if status == "live" => on_done(Ok(OfferStatus::Live)),
+                Ok(status) if status == "unknown" => on_done(Ok(OfferStatus::Unknown)),
+                Ok(_) => {
+                    let mut search_key = SearchKey::dex_lock(&lock_args);
+                    search_key.script_search_mode = "exact".to_owned();
+                    search_consumption(client, out_point, search_key, None, move |result| {
+                        on_done(result.map(|trade| OfferStatus::Consumed(trade.map(Box::new))))
+                    });
+                }
+            }
+        });
+}
+
+/// Page through the transactions touching `search_key`, newest first, until
+/// one consumes `out_point` or the indexer has no more.
+fn search_consumption(
+    client: RpcClient,
+    out_point: OutPoint,
+    search_key: SearchKey,
+    after: Option<String>,
+    on_done: impl 'static + Send + FnOnce(Result<Option<Trade>, RpcError>),
+) {
+    client.clone().get_transactions(
+        &search_key.clone(),
+        PAGE_SIZE,
+        after.as_deref(),
+        move |result| {
+            let page = match result {
+                Ok(page) => page,
+                Err(e) => return on_done(Err(e)),
+            };
+            let last_page = page.objects.len() < PAGE_SIZE as usize;
+            let cursor = page.last_cursor;
+            let inputs = page
+                .objects
+                .into_iter()
+                .filter(|r| r.io_type == "input")
+                .collect();
+            find_consumption(
+                client.clone(),
+                out_point.clone(),
+                inputs,
+                move |result| match result {
+                    Ok(None) if !last_page => {
+                        search_consumption(client, out_point, search_key, Some(cursor), on_done)
+                    }
+                    result => on_done(result),
+                },
+            );
+        },
+    );
+}
+
+/// Try `records` one by one until one of them consumes `out_point`.
+fn find_consumption(
+    client: RpcClient,
+    out_point: OutPoint,
+    mut records: VecDeque<IndexerTx>,
+    on_done: impl 'static + Send + FnOnce(Result<Option<Trade>, RpcError>),
+) {
+    let Some(record) = records.pop_front() else {
+        return on_done(Ok(None));
+    };
+    fetch_trade(&client.clone(), record, move |result| match result {
+        Err(e) => on_done(Err(e)),
+        Ok(Some(trade)) if trade.cell.out_point == out_point => on_done(Ok(Some(trade))),
+        Ok(_) => find_consumption(client, out_point, records, on_done),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpc::CellOutput;
+    use crate::tx::dex_lock_script;
+
+    const CKB: u64 = 100_000_000;
+
+    fn secp_lock(byte: u8) -> Script {
+        Script {
+            code_hash: "0x9bd7e06f3ecf4be0f2fcd2188b23f1b9fcc88e5d4b65a8637b17723bbda3cce8"
+                .to_owned(),
+            hash_type: "type".to_owned(),
+            args: format!("0x{}", hex::encode([byte; 20])),
+        }
+    }
+
+    fn output(capacity: u64, lock: Script) -> CellOutput {
+        CellOutput {
+            capacity: format!("{capacity:#x}"),
+            lock,
+            type_: None,
+        }
+    }
+
+    /// A 200 CKB mode 1 offer of `owner` priced 100 CKB.
+    fn offer(owner: &Script) -> (DexCell, DexArgs) {
+        let args = DexArgs {
+            mode: 1,
+            owner_script_hash: owner.calc_script_hash().unwrap(),
+            price_base: 1,
+            price_pow: 10,
+        };
+        let cell = DexCell::new(
+            OutPoint::new(&format!("0x{}", "ab".repeat(32)), 0),
+            "dead".to_owned(),
+            output(200 * CKB, dex_lock_script(&args)),
+            "0x".to_owned(),
+        );
+        (cell, args)
+    }
+
+    fn spending(outputs: Vec<CellOutput>) -> Transaction {
+        Transaction {
+            outputs,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn taken_when_the_owner_is_paid() {
+        let (owner, taker) = (secp_lock(0x11), secp_lock(0x22));
+        let (cell, args) = offer(&owner);
+        let inputs = [cell.output.lock.clone(), taker.clone()];
+
+        let tx = spending(vec![
+            output(300 * CKB, owner.clone()),
+            output(899 * CKB, taker.clone()),
+        ]);
+        assert_eq!(
+            classify(&cell, &args, &tx, &inputs),
+            Fill::Taken {
+                paid: 10_000_000_000
+            }
+        );
+        // the largest owner output counts
+        let tx = spending(vec![
+            output(250 * CKB, owner.clone()),
+            output(350 * CKB, owner.clone()),
+        ]);
+        assert_eq!(
+            classify(&cell, &args, &tx, &inputs),
+            Fill::Taken {
+                paid: 15_000_000_000
+            }
+        );
+        // one shannon short of capacity plus price
+        let tx = spending(vec![
+            output(300 * CKB - 1, owner.clone()),
+            output(900 * CKB, taker),
+        ]);
+        assert_eq!(classify(&cell, &args, &tx, &inputs), Fill::Cancelled);
+    }
+
+    #[test]
+    fn merged_cancel_is_cancelled() {
+        let owner = secp_lock(0x11);
+        let (cell, args) = offer(&owner);
+        // the owner spends the offer along with 598 CKB of their own into one cell
+        let inputs = [cell.output.lock.clone(), owner.clone()];
+        let tx = spending(vec![output(797 * CKB, owner.clone())]);
+        assert_eq!(classify(&cell, &args, &tx, &inputs), Fill::Cancelled);
+        // the same outputs without an input of the owner read as a take
+        let inputs = [cell.output.lock.clone(), secp_lock(0x22)];
+        assert_eq!(
+            classify(&cell, &args, &tx, &inputs),
+            Fill::Taken {
+                paid: 59_700_000_000
+            }
+        );
+    }
+}
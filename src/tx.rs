@@ -0,0 +1,81 @@
+use crate::dex::{DexArgs, DEX_LOCK_CODE_HASH, DEX_LOCK_DEP_TX_HASH, DEX_LOCK_HASH_TYPE};
+use crate::rpc::{CellDep, CellInput, CellOutput, DexCell, OutPoint, Script, Transaction};
+
+/// The dex lock code cell, every transaction unlocking a dex cell needs it.
+pub fn dex_lock_cell_dep() -> CellDep {
+    CellDep {
+        out_point: OutPoint::new(DEX_LOCK_DEP_TX_HASH, 0),
+        dep_type: "code".to_owned(),
+    }
+}
+
+pub fn dex_lock_script(args: &DexArgs) -> Script {
+    Script {
+        code_hash: DEX_LOCK_CODE_HASH.to_owned(),
+        hash_type: DEX_LOCK_HASH_TYPE.to_owned(),
+        args: args.encode(),
+    }
+}
+
+/// Unsigned skeleton putting an asset up for sale.
+///
+/// Only the dex cell output is filled in, the maker adds the inputs funding it
+/// (and their change, cell deps and witnesses) before signing.
+pub fn make_offer(
+    args: &DexArgs,
+    capacity: u64,
+    type_: Option<Script>,
+    data: String,
+) -> Transaction {
+    Transaction {
+        version: "0x0".to_owned(),
+        cell_deps: vec![],
+        header_deps: vec![],
+        inputs: vec![],
+        outputs: vec![CellOutput {
+            capacity: format!("{capacity:#x}"),
+            lock: dex_lock_script(args),
+            type_,
+        }],
+        outputs_data: vec![data],
+        witnesses: vec![],
+    }
+}
+
+/// Unsigned skeleton giving the asset in `cell` back to `owner_lock`.
+///
+/// The dex lock only allows it when an input locked by the owner is present,
+/// so the owner still has to add one of their cells (which also pays the fee),
+/// its lock's cell dep and a witness before signing.
+pub fn cancel_offer(cell: &DexCell, owner_lock: &Script) -> Transaction {
+    Transaction {
+        version: "0x0".to_owned(),
+        cell_deps: vec![dex_lock_cell_dep()],
+        header_deps: vec![],
+        inputs: vec![CellInput {
+            previous_output: cell.out_point.clone(),
+            since: "0x0".to_owned(),
+        }],
+        outputs: vec![CellOutput {
+            capacity: cell.output.capacity.clone(),
+            lock: owner_lock.clone(),
+            type_: cell.output.type_.clone(),
+        }],
+        outputs_data: vec![cell.data.clone()],
+        witnesses: vec![],
+    }
+}
+
+/// Parse a transaction as JSON, either bare or wrapped like `{"transaction": {...}}`
+/// as `ckb-cli` and most wallets export it.
+pub fn parse_transaction_json(json: &str) -> Result<Transaction, serde_json::Error> {
+    #[derive(serde::Deserialize)]
+    struct Wrapped {
+        transaction: Transaction,
+    }
+    serde_json::from_str::<Transaction>(json).or_else(|e| {
+        serde_json::from_str::<Wrapped>(json)
+            .map(|w| w.transaction)
+            .map_err(|_| e)
+    })
+}
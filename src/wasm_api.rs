@@ -0,0 +1,90 @@
+//! JavaScript bindings to the codec, `wasm-pack build --target web` generates
+//! the matching TypeScript definitions.
+//!
+//! Failures throw an `Error` whose `code` is [`DexHelperError::code`].
+
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use crate::address::Network;
+use crate::dex::{parse_owner_script_hash, DexArgs, DexHelperError, MAX_MODE};
+use crate::tx::dex_lock_script;
+
+#[wasm_bindgen(typescript_custom_section)]
+const TS_DEX_ARGS: &'static str = r#"
+export interface DexArgs {
+    mode: number;
+    /** `0x` prefixed hex */
+    owner_script_hash: string;
+    price_base: number;
+    price_pow: number;
+}
+"#;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(typescript_type = "DexArgs")]
+    pub type JsDexArgs;
+}
+
+fn to_js_error(e: DexHelperError) -> JsValue {
+    let error = js_sys::Error::new(&e.to_string());
+    js_sys::Reflect::set(&error, &"code".into(), &e.code().into())
+        .expect("setting a property on an Error can't fail");
+    error.into()
+}
+
+/// Encode dex lock args as `0x` prefixed hex.
+#[wasm_bindgen(js_name = encodeDexArgs)]
+pub fn encode_dex_args(
+    mode: u16,
+    owner_script_hash: &str,
+    price_base: u32,
+    price_pow: u32,
+) -> Result<String, JsValue> {
+    if mode > MAX_MODE {
+        return Err(to_js_error(DexHelperError::ModeTooBig));
+    }
+    let args = DexArgs {
+        mode,
+        owner_script_hash: parse_owner_script_hash(owner_script_hash.trim())
+            .map_err(to_js_error)?,
+        price_base,
+        price_pow,
+    };
+    Ok(args.encode())
+}
+
+#[wasm_bindgen(js_name = decodeDexArgs)]
+pub fn decode_dex_args(args: &str) -> Result<JsDexArgs, JsValue> {
+    let args = DexArgs::decode(args.trim()).map_err(to_js_error)?;
+    let json = serde_json::to_string(&args).expect("args always serialize");
+    Ok(js_sys::JSON::parse(&json)?.unchecked_into())
+}
+
+/// Total payment in shannons as a decimal string, `amount` is a decimal
+/// string too since neither fits in a `number`.
+#[wasm_bindgen(js_name = computePrice)]
+pub fn compute_price(args: &str, amount: &str) -> Result<String, JsValue> {
+    let args = DexArgs::decode(args.trim()).map_err(to_js_error)?;
+    let amount: u128 = amount
+        .trim()
+        .parse()
+        .map_err(|_| js_sys::Error::new(&format!("Invalid amount {amount}")))?;
+    let shannons = args.checked_total_price(amount).map_err(to_js_error)?;
+    Ok(shannons.to_string())
+}
+
+/// Full format address of the dex lock with these args.
+#[wasm_bindgen(js_name = dexLockAddress)]
+pub fn dex_lock_address(args: &str, testnet: bool) -> Result<String, JsValue> {
+    let args = DexArgs::decode(args.trim()).map_err(to_js_error)?;
+    let network = if testnet {
+        Network::Testnet
+    } else {
+        Network::Mainnet
+    };
+    Ok(dex_lock_script(&args)
+        .to_address(network)
+        .expect("the dex lock script is well formed"))
+}
@@ -0,0 +1,68 @@
+//! Exit codes and output streams of the `dex-helper-cli` binary.
+
+use std::process::{Command, Output};
+
+const OWNER: &str = "0x29e560749fc5c785e3c1cfc3e14c0e608ec87120f0c066facf023ee8bd569d84";
+
+fn cli(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_dex-helper-cli"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn encode_decode() {
+    let output = cli(&[
+        "encode", "--mode", "1", "--owner", OWNER, "--base", "100", "--pow", "8",
+    ]);
+    assert_eq!(output.status.code(), Some(0));
+    let args = String::from_utf8(output.stdout).unwrap();
+    let output = cli(&["decode", args.trim(), "--json"]);
+    assert_eq!(output.status.code(), Some(0));
+    let value: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(value["price_base"], 100);
+}
+
+#[test]
+fn codec_errors_exit_with_their_code() {
+    let mode_too_big = cli(&[
+        "encode", "--mode", "3", "--owner", OWNER, "--base", "1", "--pow", "0",
+    ]);
+    assert_eq!(mode_too_big.status.code(), Some(3));
+    let wrong_length = cli(&["decode", "0x0100"]);
+    assert_eq!(wrong_length.status.code(), Some(4));
+    let overflow = cli(&[
+        "price",
+        "--amount",
+        &u128::MAX.to_string(),
+        "--mode",
+        "0",
+        "--base",
+        "4294967295",
+        "--pow",
+        "15",
+    ]);
+    assert_eq!(overflow.status.code(), Some(5));
+}
+
+#[test]
+fn bad_command_lines_exit_64() {
+    let no_command = cli(&[]);
+    assert_eq!(no_command.status.code(), Some(64));
+    assert!(no_command.stdout.is_empty());
+    assert!(String::from_utf8_lossy(&no_command.stderr).contains("Usage:"));
+    assert_eq!(cli(&["frobnicate"]).status.code(), Some(64));
+    assert_eq!(cli(&["encode", "--mode", "1"]).status.code(), Some(64));
+    // asking for help isn't an error
+    assert_eq!(cli(&["--help"]).status.code(), Some(0));
+}
+
+#[test]
+fn json_errors_go_to_stderr() {
+    let output = cli(&["decode", "0x0100", "--json"]);
+    assert_eq!(output.status.code(), Some(4));
+    assert!(output.stdout.is_empty());
+    let error: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(error["code"], 4);
+}
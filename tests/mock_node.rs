@@ -0,0 +1,297 @@
+//! The RPC client against the in-process mock node, serving `examples/snapshot.json`.
+
+use std::sync::mpsc;
+use std::time::Duration;
+
+use dex_helper::dex::DexArgs;
+use dex_helper::mock_node::{ChainSnapshot, MockNode};
+use dex_helper::rpc::{
+    CellInput, CellOutput, DexCell, OutPoint, RpcClient, RpcError, Script, SearchKey, Transaction,
+};
+use dex_helper::trade::{fetch_offer_status, Fill, OfferStatus};
+use dex_helper::tx::{cancel_offer, dex_lock_cell_dep, make_offer};
+
+/// Hash of the transaction in `examples/snapshot.json`.
+const GENESIS: &str = "0x0000000000000000000000000000000000000000000000000000000000000001";
+
+fn start() -> (MockNode, RpcClient) {
+    let snapshot: ChainSnapshot =
+        serde_json::from_str(include_str!("../examples/snapshot.json")).unwrap();
+    let node = MockNode::start(snapshot).unwrap();
+    let client = RpcClient::new(node.url());
+    (node, client)
+}
+
+const CKB: u64 = 100_000_000;
+
+/// The secp256k1 lock of the genesis cells, `0x11..` owns output 0, `0x22..` output 1.
+fn secp_lock(byte: u8) -> Script {
+    Script {
+        code_hash: "0x9bd7e06f3ecf4be0f2fcd2188b23f1b9fcc88e5d4b65a8637b17723bbda3cce8".to_owned(),
+        hash_type: "type".to_owned(),
+        args: format!("0x{}", hex::encode([byte; 20])),
+    }
+}
+
+fn input(out_point: OutPoint) -> CellInput {
+    CellInput {
+        previous_output: out_point,
+        since: "0x0".to_owned(),
+    }
+}
+
+fn output(capacity: u64, lock: Script) -> CellOutput {
+    CellOutput {
+        capacity: format!("{capacity:#x}"),
+        lock,
+        type_: None,
+    }
+}
+
+/// Wait for the result of a callback based RPC.
+fn wait<T: Send + 'static>(call: impl FnOnce(Box<dyn FnOnce(T) + Send>)) -> T {
+    let (tx, rx) = mpsc::channel();
+    call(Box::new(move |result| tx.send(result).unwrap()));
+    rx.recv_timeout(Duration::from_secs(10))
+        .expect("the mock node didn't answer")
+}
+
+#[test]
+fn fetch_live_dex_cell() {
+    let (_node, client) = start();
+    let cell = wait(|done| client.fetch_dex_cell(OutPoint::new(GENESIS, 2), done)).unwrap();
+    assert!(cell.is_live());
+    assert_eq!(cell.output.capacity(), Some(20_000_000_000));
+    let args = cell.args.unwrap();
+    assert_eq!(args.mode, 1);
+    assert_eq!(args.price_base, 100);
+    assert_eq!(args.price_pow, 8);
+
+    // UDT mode cell, with the amount in its data
+    let cell = wait(|done| client.fetch_dex_cell(OutPoint::new(GENESIS, 3), done)).unwrap();
+    assert_eq!(cell.args.as_ref().unwrap().mode, 0);
+    assert_eq!(
+        dex_helper::dex::udt_amount(&cell.data_bytes()),
+        Some(100_000_000_000)
+    );
+}
+
+#[test]
+fn fetch_unknown_cell() {
+    let (_node, client) = start();
+    let missing = OutPoint::new(&format!("0x{}", "ab".repeat(32)), 0);
+    let result = wait(|done| client.fetch_dex_cell(missing, done));
+    assert!(matches!(result, Err(RpcError::NotFound(_))), "{result:?}");
+
+    // past the last output of a known transaction
+    let result = wait(|done| client.fetch_dex_cell(OutPoint::new(GENESIS, 9), done));
+    assert!(matches!(result, Err(RpcError::NotFound(_))), "{result:?}");
+
+    // a secp256k1 cell is no order, whatever its args
+    let result = wait(|done| client.fetch_dex_cell(OutPoint::new(GENESIS, 0), done));
+    assert!(matches!(result, Err(RpcError::NotDexCell(_))), "{result:?}");
+}
+
+#[test]
+fn get_cells_pages() {
+    let (_node, client) = start();
+    let search_key = SearchKey::dex_lock("0x");
+    let mut out_points = vec![];
+    let mut after = None;
+    loop {
+        let page = wait(|done| client.get_cells(&search_key, 1, after.as_deref(), done)).unwrap();
+        if page.objects.is_empty() {
+            break;
+        }
+        assert_eq!(page.objects.len(), 1);
+        out_points.extend(page.objects.into_iter().map(|cell| cell.out_point));
+        after = Some(page.last_cursor);
+    }
+    assert_eq!(
+        out_points,
+        [OutPoint::new(GENESIS, 2), OutPoint::new(GENESIS, 3)]
+    );
+
+    // the prefix narrows the search down to one mode
+    let page =
+        wait(|done| client.get_cells(&SearchKey::dex_lock("0x0000"), 10, None, done)).unwrap();
+    assert_eq!(page.objects.len(), 1);
+    assert_eq!(page.objects[0].out_point, OutPoint::new(GENESIS, 3));
+}
+
+fn send(client: &RpcClient, tx: &Transaction) -> Result<String, RpcError> {
+    wait(|done| client.send_transaction(tx, done))
+}
+
+/// Put a 200 CKB cell of `owner` up for 100 CKB, paid for by `funding` (holding `funds`).
+///
+/// Outputs the offer, the change and a 100 CKB cell of the owner to pay a cancel's fee with.
+fn make(client: &RpcClient, owner: &Script, funding: OutPoint, funds: u64) -> (DexArgs, String) {
+    let args = DexArgs {
+        mode: 1,
+        owner_script_hash: owner.calc_script_hash().unwrap(),
+        price_base: 1,
+        price_pow: 10,
+    };
+    let mut tx = make_offer(&args, 200 * CKB, None, "0x".to_owned());
+    tx.inputs.push(input(funding));
+    tx.outputs.push(output(funds - 201 * CKB, owner.clone()));
+    tx.outputs_data.push("0x".to_owned());
+    let hash = send(client, &tx).unwrap();
+    (args, hash)
+}
+
+/// Cancel `cell` spending the owner's `change` along with it, into one cell of `capacity`.
+fn merged_cancel(cell: &DexCell, owner: &Script, change: OutPoint, capacity: u64) -> Transaction {
+    let mut cancel = cancel_offer(cell, owner);
+    cancel.inputs.push(input(change));
+    cancel.outputs[0].capacity = format!("{capacity:#x}");
+    cancel
+}
+
+fn offer_status(client: &RpcClient, out_point: OutPoint, args: &DexArgs) -> OfferStatus {
+    wait(|done| fetch_offer_status(client, out_point, &args.encode(), done)).unwrap()
+}
+
+#[test]
+fn make_take_cancel() {
+    let (_node, client) = start();
+    let owner = secp_lock(0x11);
+    let taker = secp_lock(0x22);
+
+    let (args, made) = make(&client, &owner, OutPoint::new(GENESIS, 0), 1000 * CKB);
+    let offer = OutPoint::new(&made, 0);
+    let cell = wait(|done| client.fetch_dex_cell(offer.clone(), done)).unwrap();
+    assert!(cell.is_live());
+    assert_eq!(cell.args, Ok(args.clone()));
+    assert!(matches!(
+        offer_status(&client, offer.clone(), &args),
+        OfferStatus::Live
+    ));
+
+    // the owner must get the cell capacity plus the price, 300 CKB
+    let take = |paid: u64| Transaction {
+        version: "0x0".to_owned(),
+        cell_deps: vec![dex_lock_cell_dep()],
+        inputs: vec![input(offer.clone()), input(OutPoint::new(GENESIS, 1))],
+        outputs: vec![
+            output(paid, owner.clone()),
+            output(1199 * CKB - paid, taker.clone()),
+        ],
+        outputs_data: vec!["0x".to_owned(), "0x".to_owned()],
+        ..Default::default()
+    };
+    let error = send(&client, &take(250 * CKB)).unwrap_err();
+    let failure = error.script_failure().expect("a script failure");
+    assert_eq!(failure.source, "Inputs[0].Lock");
+    assert_eq!(failure.exit_code, 7);
+    assert!(wait(|done| client.fetch_dex_cell(offer.clone(), done))
+        .unwrap()
+        .is_live());
+
+    let taken = send(&client, &take(300 * CKB)).unwrap();
+    let cell = wait(|done| client.fetch_dex_cell(offer.clone(), done)).unwrap();
+    assert_eq!(cell.status, "dead");
+    match offer_status(&client, offer.clone(), &args) {
+        OfferStatus::Consumed(Some(trade)) => {
+            assert_eq!(trade.tx_hash, taken);
+            assert_eq!(
+                trade.fill,
+                Fill::Taken {
+                    paid: 100 * CKB as u128
+                }
+            );
+        }
+        status => panic!("{status:?}"),
+    }
+    // it can't be taken twice
+    let error = send(&client, &take(300 * CKB)).unwrap_err();
+    assert!(error.to_string().contains("Dead"), "{error}");
+
+    // the owner cancels another offer, merging it with their change
+    let (args, made) = make(&client, &owner, OutPoint::new(&made, 1), 799 * CKB);
+    let offer = OutPoint::new(&made, 0);
+    let cell = wait(|done| client.fetch_dex_cell(offer.clone(), done)).unwrap();
+    let cancel = merged_cancel(&cell, &owner, OutPoint::new(&made, 1), 797 * CKB);
+    let cancelled = send(&client, &cancel).unwrap();
+    match offer_status(&client, offer, &args) {
+        OfferStatus::Consumed(Some(trade)) => {
+            assert_eq!(trade.tx_hash, cancelled);
+            assert_eq!(trade.fill, Fill::Cancelled);
+        }
+        status => panic!("{status:?}"),
+    }
+}
+
+#[test]
+fn duplicate_inputs_are_rejected() {
+    let (_node, client) = start();
+    let owner = secp_lock(0x11);
+    let mut tx = Transaction {
+        version: "0x0".to_owned(),
+        inputs: vec![
+            input(OutPoint::new(GENESIS, 1)),
+            input(OutPoint::new(GENESIS, 1)),
+        ],
+        outputs: vec![output(100 * CKB, owner)],
+        outputs_data: vec!["0x".to_owned()],
+        ..Default::default()
+    };
+    let error = send(&client, &tx).unwrap_err();
+    assert!(error.to_string().contains("Dead"), "{error}");
+    // the cell is still there to spend once
+    tx.inputs.pop();
+    send(&client, &tx).unwrap();
+}
+
+#[test]
+fn answers_cors_preflight() {
+    let (node, _client) = start();
+    let request = ehttp::Request {
+        method: "OPTIONS".to_owned(),
+        ..ehttp::Request::get(node.url())
+    };
+    let response = ehttp::fetch_blocking(&request).unwrap();
+    assert_eq!(response.status, 204);
+    assert_eq!(
+        response.headers.get("Access-Control-Allow-Origin"),
+        Some("*")
+    );
+    assert_eq!(
+        response.headers.get("Access-Control-Allow-Methods"),
+        Some("POST, OPTIONS")
+    );
+    assert_eq!(
+        response.headers.get("Access-Control-Allow-Headers"),
+        Some("Content-Type")
+    );
+}
+
+#[test]
+fn offer_status_pages_through_busy_locks() {
+    let (_node, client) = start();
+    let owner = secp_lock(0x11);
+    let (args, made) = make(&client, &owner, OutPoint::new(GENESIS, 0), 1000 * CKB);
+    let offer = OutPoint::new(&made, 0);
+    let cell = wait(|done| client.fetch_dex_cell(offer.clone(), done)).unwrap();
+    let cancel = merged_cancel(&cell, &owner, OutPoint::new(&made, 1), 998 * CKB);
+    let cancelled = send(&client, &cancel).unwrap();
+
+    // more than a page of newer offers with the same args
+    let mut funding = (OutPoint::new(&cancelled, 0), 998 * CKB);
+    for _ in 0..101 {
+        let mut tx = make_offer(&args, CKB, None, "0x".to_owned());
+        tx.inputs.push(input(funding.0));
+        tx.outputs.push(output(funding.1 - CKB, owner.clone()));
+        tx.outputs_data.push("0x".to_owned());
+        funding = (
+            OutPoint::new(&send(&client, &tx).unwrap(), 1),
+            funding.1 - CKB,
+        );
+    }
+
+    match offer_status(&client, offer, &args) {
+        OfferStatus::Consumed(Some(trade)) => assert_eq!(trade.tx_hash, cancelled),
+        status => panic!("{status:?}"),
+    }
+}